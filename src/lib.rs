@@ -1,8 +1,45 @@
-use std::{convert::Infallible, str::FromStr, time};
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashSet},
+    env, error,
+    ffi::OsString,
+    fmt, fs, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time,
+};
 
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::{
+    builder::{OsStr, Resettable},
+    Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum,
+};
+use clap_complete::Shell;
 
+/// The file, relative to a repository's working directory, holding its RID.
+pub const RID_FILE: &str = ".rad/rid";
+
+pub const DEFAULT_REPLICAS: usize = 3;
 pub const DEFAULT_SYNC_TIMEOUT: time::Duration = time::Duration::from_secs(9);
+pub const DEFAULT_ANNOUNCE_TIMEOUT: time::Duration = time::Duration::from_secs(3);
+pub const DEFAULT_STATUS_TIMEOUT: time::Duration = time::Duration::from_secs(3);
+pub const DEFAULT_ACK_TIMEOUT: time::Duration = time::Duration::from_secs(9);
+/// The longest sync timeout that may be given. Use `--no-timeout` to wait
+/// however long it takes.
+pub const MAX_SYNC_TIMEOUT: time::Duration = time::Duration::from_secs(60 * 60);
+
+/// Environment variable overriding the default sync timeout, in seconds.
+pub const ENV_SYNC_TIMEOUT: &str = "RAD_SYNC_TIMEOUT";
+/// Environment variable overriding the default replica count.
+pub const ENV_SYNC_REPLICAS: &str = "RAD_SYNC_REPLICAS";
+/// Environment variable overriding the default seeds, separated by commas.
+pub const ENV_SYNC_SEEDS: &str = "RAD_SYNC_SEEDS";
+/// Environment variable holding the Radicle home directory.
+pub const ENV_RAD_HOME: &str = "RAD_HOME";
+
+/// The config file, relative to the Radicle home directory, read when no
+/// `--config` is given.
+pub const CONFIG_FILE: &str = "sync.toml";
 
 // Looking at the `rad sync` code, these are the possible calls we can make:
 //
@@ -69,7 +106,7 @@ pub const DEFAULT_SYNC_TIMEOUT: time::Duration = time::Duration::from_secs(9);
 // Sync repositories to and from the network
 //
 // Usage:
-//   rad sync [--fetch | --announce] [--rid <rid>] [--timeout <secs>] [--debug] [--seed <nid>]
+//   rad sync [--fetch | --announce] [--rid <rid>] [--timeout <duration>] [--debug] [--seed <nid>]
 //   rad sync status [--sort-by <field>]
 //   rad sync --inventory
 
@@ -165,27 +202,87 @@ const SYNC_HELP: &str = r#"    By default, the current repository is synchronize
     to be matched. For example, `--replicas 5` will sync with 5 seeds.
 
     When `--fetch` or `--announce` are specified on their own, this command
-    will only fetch or announce.
+    will only fetch or announce. Likewise, `--no-fetch` and `--no-announce`
+    turn off fetching or announcing.
+
+    `rad sync inventory` announces the node's inventory to the network.
+    This mode does not take an `<rid>`. The `--inventory` flag is an older
+    spelling of the same mode.
+"#;
 
-    If `--inventory` is specified, the node's inventory is announced to
-    the network. This mode does not take an `<rid>`.
+const SYNC_USAGE: &str = r#"  rad sync [--fetch | --announce] [--rid <rid> | --name <name> | --repo-path <dir>] [--timeout <duration>] [--debug] [--seed <nid>]
+  rad sync status [--sort-by <field>] [--order <order>]
+  rad sync inventory
 "#;
 
-const SYNC_USAGE: &str = r#"  rad sync [--fetch | --announce] [--rid <rid>] [--timeout <secs>] [--debug] [--seed <nid>]
-  rad sync status [--sort-by <field>]
-  rad sync --inventory
+const STATUS_EXAMPLES: &str = r#"Examples
+
+    rad sync status --sort-by alias
+    rad sync status --sort-by status --order desc
+    rad sync status --rid <rid> --compact
+    rad sync status --template '{alias} {status}'
 "#;
 
 #[derive(Debug, Clone, PartialEq, Eq, Parser)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "op", rename_all = "snake_case")
+)]
 pub enum Operation {
     /// Display the whether other nodes are synced or out-of-sync with this
     /// node's signed references
-    #[command(override_usage = "rad sync status [--sort-by <field>]")]
+    #[command(override_usage = "rad sync status [--sort-by <field>] [--order <order>]")]
+    #[command(after_help = STATUS_EXAMPLES)]
     #[clap(help_template = HELP_NO_COMMANDS)]
     Status {
         /// Sort by sync status
         #[arg(long, value_name = "field", value_enum, default_value_t)]
         sort_by: SortBy,
+        /// Sort in ascending or descending order
+        #[arg(long, value_name = "order", value_enum, default_value_t)]
+        order: SortOrder,
+        /// Render the sync status as a single glyph, e.g. `✓`, `✗`, or `~`
+        #[arg(long)]
+        compact: bool,
+        /// Suppress output and exit with the number of out-of-sync nodes
+        #[arg(long)]
+        count_only: bool,
+        /// How long to wait while collecting the sync status
+        #[arg(
+            long,
+            value_name = "duration",
+            value_parser = parse_duration,
+            default_value = format_duration(DEFAULT_STATUS_TIMEOUT)
+        )]
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_secs"))]
+        status_timeout: time::Duration,
+        /// Render each row using the given template, e.g. `{nid} {status}`.
+        /// Placeholders are `{nid}`, `{alias}`, `{status}` and `{last_synced}`
+        #[arg(long, value_name = "template")]
+        template: Option<StatusTemplate>,
+        /// Truncate table cells longer than N characters
+        #[arg(long, value_name = "n")]
+        max_col_width: Option<usize>,
+        /// Only include these fields in JSON output, separated by commas
+        #[arg(long, value_name = "fields", value_enum, value_delimiter = ',')]
+        fields: Vec<StatusField>,
+        /// Display the sync status of all local repositories
+        #[arg(long, conflicts_with_all = ["rid", "name", "repo_path"])]
+        all: bool,
+    },
+    /// Announce the node's inventory to the network
+    #[command(override_usage = "rad sync inventory")]
+    #[clap(help_template = HELP_NO_COMMANDS)]
+    Inventory,
+    /// Write a shell completion script for `rad` to stdout
+    #[command(hide = true)]
+    #[clap(help_template = HELP_NO_COMMANDS)]
+    Completions {
+        /// The shell to complete for
+        #[arg(value_enum, value_name = "shell")]
+        #[cfg_attr(feature = "serde", serde(skip))]
+        shell: Shell,
     },
 }
 
@@ -193,11 +290,98 @@ impl Default for Operation {
     fn default() -> Self {
         Self::Status {
             sort_by: SortBy::default(),
+            order: SortOrder::default(),
+            compact: false,
+            count_only: false,
+            status_timeout: DEFAULT_STATUS_TIMEOUT,
+            template: None,
+            max_col_width: None,
+            fields: Vec::new(),
+            all: false,
+        }
+    }
+}
+
+impl Operation {
+    /// The field and order to sort the status table by.
+    pub fn sort(&self) -> (SortBy, SortOrder) {
+        match self {
+            Self::Status { sort_by, order, .. } => (*sort_by, *order),
+            Self::Inventory | Self::Completions { .. } => (SortBy::default(), SortOrder::default()),
+        }
+    }
+
+    /// How long to wait while collecting the sync status. This is
+    /// independent of the sync `--timeout`.
+    pub fn status_timeout(&self) -> time::Duration {
+        match self {
+            Self::Status { status_timeout, .. } => *status_timeout,
+            Self::Inventory | Self::Completions { .. } => DEFAULT_STATUS_TIMEOUT,
+        }
+    }
+
+    /// The `rad sync` arguments that select this operation and change what
+    /// it does, omitting any that match the defaults. Flags that only change
+    /// how the status is shown are left out: `--order`, `--compact`,
+    /// `--count-only`, `--template`, `--max-col-width` and `--fields`.
+    fn key_args(&self) -> Vec<String> {
+        match self {
+            Self::Status {
+                sort_by,
+                status_timeout,
+                all,
+                ..
+            } => {
+                let mut args = vec!["status".to_owned()];
+                if *sort_by != SortBy::default() {
+                    args.extend(["--sort-by".to_owned(), sort_by.to_string()]);
+                }
+                if *status_timeout != DEFAULT_STATUS_TIMEOUT {
+                    args.extend([
+                        "--status-timeout".to_owned(),
+                        format_duration(*status_timeout),
+                    ]);
+                }
+                if *all {
+                    args.push("--all".to_owned());
+                }
+                args
+            }
+            Self::Inventory => vec!["inventory".to_owned()],
+            Self::Completions { shell } => vec!["completions".to_owned(), shell.to_string()],
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status {
+                sort_by,
+                order,
+                all,
+                ..
+            } => write!(
+                f,
+                "showing sync status of {}, sorted by {sort_by} in {} order",
+                if *all { "all repos" } else { "repo" },
+                match order {
+                    SortOrder::Asc => "ascending",
+                    SortOrder::Desc => "descending",
+                }
+            ),
+            Self::Inventory => write!(f, "announcing inventory"),
+            Self::Completions { shell } => write!(f, "writing {shell} completions"),
         }
     }
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum SortBy {
     /// Sort by Node ID
     Nid,
@@ -209,21 +393,406 @@ pub enum SortBy {
 }
 
 impl FromStr for SortBy {
-    type Err = &'static str;
+    type Err = ParseSortByError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "nid" => Ok(Self::Nid),
-            "alias" => Ok(Self::Alias),
-            "status" => Ok(Self::Status),
-            _ => Err("invalid `--sort-by` field"),
+        // Defer to the `ValueEnum` derive, so that both accept the same names.
+        <Self as ValueEnum>::from_str(s, false).map_err(|_| ParseSortByError {
+            input: s.to_owned(),
+        })
+    }
+}
+
+/// A string that is not a valid [`SortBy`] field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSortByError {
+    /// The string that failed to parse.
+    pub input: String,
+}
+
+impl fmt::Display for ParseSortByError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let expected = SortBy::value_variants()
+            .iter()
+            .filter_map(ValueEnum::to_possible_value)
+            .map(|value| value.get_name().to_owned())
+            .collect::<Vec<_>>();
+
+        write!(
+            f,
+            "invalid --sort-by field: `{}`, expected one of {}",
+            self.input,
+            expected.join(", ")
+        )
+    }
+}
+
+impl error::Error for ParseSortByError {}
+
+impl fmt::Display for SortBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nid => write!(f, "node id"),
+            Self::Alias => write!(f, "alias"),
+            Self::Status => write!(f, "sync status"),
+        }
+    }
+}
+
+impl SortBy {
+    /// Sort the rows by this field, in ascending order.
+    ///
+    /// The sort is stable: rows with equal keys keep their input order.
+    pub fn sort_rows<R: StatusRow>(&self, rows: &mut [R]) {
+        self.sort_rows_in(SortOrder::Asc, rows)
+    }
+
+    /// Sort the nodes by this field, in ascending order. Nodes carry no sync
+    /// state, so sorting by status sorts by Node ID.
+    pub fn sort_nodes<N: Peer>(&self, nodes: &mut [N]) {
+        nodes.sort_by(|a, b| match self {
+            Self::Nid | Self::Status => a.nid().cmp(b.nid()),
+            Self::Alias => cmp_aliases((a.alias(), a.nid()), (b.alias(), b.nid())),
+        })
+    }
+
+    /// Sort the rows by this field, in the given order.
+    ///
+    /// The sort is stable in either order: rows with equal keys keep their
+    /// input order.
+    pub fn sort_rows_in<R: StatusRow>(&self, order: SortOrder, rows: &mut [R]) {
+        rows.sort_by(|a, b| {
+            let ordering = match self {
+                Self::Nid => a.nid().cmp(b.nid()),
+                Self::Alias => cmp_aliases((a.alias(), a.nid()), (b.alias(), b.nid())),
+                Self::Status => a.state().cmp(&b.state()),
+            };
+            match order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        })
+    }
+}
+
+/// The order to sort the status table in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum SortOrder {
+    /// Sort in ascending order (default)
+    #[default]
+    Asc,
+    /// Sort in descending order
+    Desc,
+}
+
+/// Compare nodes by alias, ignoring case, and then by Node ID. Nodes
+/// without an alias come first.
+fn cmp_aliases(a: (Option<&str>, &NodeId), b: (Option<&str>, &NodeId)) -> std::cmp::Ordering {
+    let fold = |alias: Option<&str>| alias.map(str::to_lowercase);
+
+    fold(a.0).cmp(&fold(b.0)).then_with(|| a.1.cmp(b.1))
+}
+
+/// A node that can be sorted by [`SortBy::sort_nodes`].
+pub trait Peer {
+    /// The Node ID of the peer.
+    fn nid(&self) -> &NodeId;
+    /// The alias of the peer, if it has one.
+    fn alias(&self) -> Option<&str>;
+}
+
+/// A row of the sync status table, for a [`Peer`].
+pub trait StatusRow: Peer {
+    /// The sync state of the peer.
+    fn state(&self) -> SyncState;
+    /// When the peer was last synced, as a Unix timestamp in seconds.
+    fn last_synced(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// Truncate a table cell to at most `max` characters, replacing the last
+/// visible character with an ellipsis if anything was cut off.
+pub fn truncate_cell(cell: &str, max: usize) -> String {
+    if cell.chars().count() <= max {
+        return cell.to_owned();
+    }
+    let mut truncated = cell.chars().take(max.saturating_sub(1)).collect::<String>();
+    if max > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// A field of a status row, as selected by `--fields`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum StatusField {
+    /// The Node ID of the peer
+    Nid,
+    /// The alias of the peer
+    Alias,
+    /// The sync status of the peer
+    Status,
+    /// When the peer was last synced
+    #[value(name = "last_synced")]
+    LastSynced,
+}
+
+impl StatusField {
+    /// All fields, in the order they are output.
+    pub const ALL: [StatusField; 4] = [Self::Nid, Self::Alias, Self::Status, Self::LastSynced];
+
+    /// The field's name, as used for its JSON key.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Nid => "nid",
+            Self::Alias => "alias",
+            Self::Status => "status",
+            Self::LastSynced => "last_synced",
+        }
+    }
+}
+
+/// Project a row onto the given fields as a JSON object. If no fields are
+/// given, all of them are included.
+#[cfg(feature = "serde")]
+pub fn project_row(
+    row: &impl StatusRow,
+    fields: &[StatusField],
+) -> serde_json::Map<String, serde_json::Value> {
+    let fields = if fields.is_empty() {
+        &StatusField::ALL[..]
+    } else {
+        fields
+    };
+    fields
+        .iter()
+        .map(|field| {
+            let value = match field {
+                StatusField::Nid => serde_json::Value::from(row.nid().to_string()),
+                StatusField::Alias => serde_json::Value::from(row.alias()),
+                StatusField::Status => serde_json::Value::from(row.state().to_string()),
+                StatusField::LastSynced => serde_json::Value::from(row.last_synced()),
+            };
+            (field.name().to_owned(), value)
+        })
+        .collect()
+}
+
+/// A template for rendering status rows, given by `--template`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusTemplate(Vec<TemplatePart>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Nid,
+    Alias,
+    Status,
+    LastSynced,
+}
+
+impl fmt::Display for StatusTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.0 {
+            match part {
+                TemplatePart::Text(text) => f.write_str(text)?,
+                TemplatePart::Nid => f.write_str("{nid}")?,
+                TemplatePart::Alias => f.write_str("{alias}")?,
+                TemplatePart::Status => f.write_str("{status}")?,
+                TemplatePart::LastSynced => f.write_str("{last_synced}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for StatusTemplate {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl StatusTemplate {
+    /// Render a row, substituting `-` for missing values.
+    pub fn render(&self, row: &impl StatusRow) -> String {
+        let mut out = String::new();
+        for part in &self.0 {
+            match part {
+                TemplatePart::Text(text) => out.push_str(text),
+                TemplatePart::Nid => out.push_str(&row.nid().0),
+                TemplatePart::Alias => out.push_str(row.alias().unwrap_or("-")),
+                TemplatePart::Status => out.push_str(&row.state().to_string()),
+                TemplatePart::LastSynced => match row.last_synced() {
+                    Some(time) => out.push_str(&time.to_string()),
+                    None => out.push('-'),
+                },
+            }
+        }
+        out
+    }
+}
+
+impl FromStr for StatusTemplate {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut rest = s;
+
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(TemplatePart::Text(rest[..start].to_owned()));
+            }
+            let Some(end) = rest[start..].find('}') else {
+                return Err(TemplateError::Unclosed);
+            };
+            let part = match &rest[start + 1..start + end] {
+                "nid" => TemplatePart::Nid,
+                "alias" => TemplatePart::Alias,
+                "status" => TemplatePart::Status,
+                "last_synced" => TemplatePart::LastSynced,
+                other => return Err(TemplateError::UnknownPlaceholder(other.to_owned())),
+            };
+            parts.push(part);
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(TemplatePart::Text(rest.to_owned()));
+        }
+        Ok(Self(parts))
+    }
+}
+
+/// An invalid `--template`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A placeholder that is not a known field.
+    UnknownPlaceholder(String),
+    /// A `{` without a matching `}`.
+    Unclosed,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownPlaceholder(name) => write!(
+                f,
+                "unknown placeholder `{{{name}}}`, expected one of \
+                 {{nid}}, {{alias}}, {{status}}, {{last_synced}}"
+            ),
+            Self::Unclosed => write!(f, "unclosed `{{` in template"),
+        }
+    }
+}
+
+impl error::Error for TemplateError {}
+
+/// The sync state of a node's signed references, relative to our own.
+///
+/// States are ordered from synced to unknown, which is the order used when
+/// sorting by status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum SyncState {
+    /// The node has our latest signed references.
+    Synced,
+    /// The node has signed references that differ from ours.
+    OutOfSync,
+    /// The node's signed references could not be determined.
+    Unknown,
+}
+
+impl fmt::Display for SyncState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Synced => write!(f, "synced"),
+            Self::OutOfSync => write!(f, "out-of-sync"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl SyncState {
+    /// The state of a peer, given the head of our signed references and the
+    /// head the peer has for them, if known. The peer is synced if the heads
+    /// match, out of sync if they differ, and unknown if the peer's head is
+    /// not known.
+    pub fn from_comparison(local_head: &str, remote_head: Option<&str>) -> Self {
+        match remote_head {
+            Some(remote) if remote == local_head => Self::Synced,
+            Some(_) => Self::OutOfSync,
+            None => Self::Unknown,
+        }
+    }
+
+    /// The single character used to render the state in compact output.
+    pub fn glyph(&self) -> char {
+        match self {
+            Self::Synced => '✓',
+            Self::OutOfSync => '✗',
+            Self::Unknown => '~',
+        }
+    }
+}
+
+/// The number of nodes in each [`SyncState`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatusSummary {
+    pub synced: usize,
+    pub out_of_sync: usize,
+    pub unknown: usize,
+}
+
+impl StatusSummary {
+    /// The exit code for `--count-only`: the number of out-of-sync nodes,
+    /// capped at 255.
+    pub fn exit_code(&self) -> u8 {
+        u8::try_from(self.out_of_sync).unwrap_or(u8::MAX)
+    }
+}
+
+impl FromIterator<SyncState> for StatusSummary {
+    fn from_iter<I: IntoIterator<Item = SyncState>>(iter: I) -> Self {
+        let mut summary = Self::default();
+        for state in iter {
+            match state {
+                SyncState::Synced => summary.synced += 1,
+                SyncState::OutOfSync => summary.out_of_sync += 1,
+                SyncState::Unknown => summary.unknown += 1,
+            }
         }
+        summary
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "mode", rename_all = "lowercase")
+)]
 pub enum SyncMode {
     Repo {
+        /// The repositories given by `--rid`, without repeats. Empty if the
+        /// repository is resolved some other way.
+        rids: Vec<RepoId>,
+        #[cfg_attr(feature = "serde", serde(flatten))]
         settings: SyncSettings,
         direction: SyncDirection,
     },
@@ -231,43 +800,207 @@ pub enum SyncMode {
 }
 
 impl SyncMode {
-    pub fn new(args: SyncModeArgs, settings: Option<SyncSettings>) -> SyncMode {
+    /// The sync mode for the given arguments. The `rids` are ignored when
+    /// announcing the inventory.
+    pub fn new(args: SyncModeArgs, settings: Option<SyncSettings>, rids: Vec<RepoId>) -> SyncMode {
         if args.inventory {
             SyncMode::Inventory
         } else {
+            let mut seen = HashSet::new();
             SyncMode::Repo {
+                rids: rids
+                    .into_iter()
+                    .filter(|rid| seen.insert(rid.clone()))
+                    .collect(),
                 settings: settings.unwrap_or_default(),
                 direction: SyncDirection::from(args.directions),
             }
         }
     }
+
+    /// The `rad sync` arguments that reproduce this mode, omitting any that
+    /// match the defaults. See [`SyncSettings::to_args`].
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            Self::Repo {
+                rids,
+                settings,
+                direction,
+            } => {
+                let mut args = match direction {
+                    SyncDirection::Fetch => vec!["--fetch".to_owned()],
+                    SyncDirection::Announce => vec!["--announce".to_owned()],
+                    SyncDirection::Both => Vec::new(),
+                };
+                for rid in rids {
+                    args.extend(["--rid".to_owned(), rid.to_string()]);
+                }
+                args.extend(settings.args_for(direction));
+                args
+            }
+            Self::Inventory => vec!["--inventory".to_owned()],
+        }
+    }
+
+    /// The request to send to the node to carry out this mode for one
+    /// repository. The `rid` is only included for repository syncs.
+    pub fn to_request(&self, rid: Option<&RepoId>) -> SyncRequest {
+        match self {
+            Self::Repo {
+                settings,
+                direction,
+                ..
+            } => SyncRequest {
+                mode: SyncRequestKind::Repo,
+                rid: rid.cloned(),
+                direction: Some(direction.clone()),
+                replicas: Some(settings.replicas),
+                seeds: settings.effective_seeds(),
+                timeout: settings.timeout,
+            },
+            Self::Inventory => SyncRequest {
+                mode: SyncRequestKind::Inventory,
+                rid: None,
+                direction: None,
+                replicas: None,
+                seeds: Vec::new(),
+                timeout: None,
+            },
+        }
+    }
+
+    /// Whether this mode operates on a repository, and so needs a RID to be
+    /// given or resolved.
+    pub fn requires_rid(&self) -> bool {
+        match self {
+            Self::Repo { .. } => true,
+            Self::Inventory => false,
+        }
+    }
+}
+
+impl fmt::Display for SyncMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Repo {
+                rids,
+                settings,
+                direction,
+            } => {
+                write!(
+                    f,
+                    "syncing {} ({direction}) with {} and {}",
+                    match rids.len() {
+                        0 | 1 => "repo".to_owned(),
+                        n => format!("{n} repos"),
+                    },
+                    match settings.replicas {
+                        ReplicaTarget::Exact(n) => plural(n, "replica"),
+                        ReplicaTarget::Max => "as many replicas as possible".to_owned(),
+                    },
+                    plural(settings.seeds.len(), "seed"),
+                )?;
+                if let Some(ack_timeout) = settings.ack_timeout {
+                    write!(
+                        f,
+                        ", waiting up to {} for acknowledgements",
+                        format_duration(ack_timeout)
+                    )?;
+                }
+                Ok(())
+            }
+            Self::Inventory => write!(f, "announcing inventory"),
+        }
+    }
+}
+
+/// A sync request as sent to the node, see [`SyncMode::to_request`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SyncRequest {
+    /// What is being synced.
+    pub mode: SyncRequestKind,
+    /// The repository to sync, for repository syncs.
+    pub rid: Option<RepoId>,
+    /// Whether to fetch, announce or both, for repository syncs.
+    pub direction: Option<SyncDirection>,
+    /// The replica target, for repository syncs.
+    pub replicas: Option<ReplicaTarget>,
+    /// The seeds to sync with, in the order they should be dialed.
+    pub seeds: Vec<Seed>,
+    /// How long to wait for syncing to complete, or `None` to wait however
+    /// long it takes. Sent as a whole number of milliseconds, the finest
+    /// unit [`parse_duration`] accepts.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "timeout_ms", serialize_with = "serialize_opt_millis")
+    )]
+    pub timeout: Option<time::Duration>,
+}
+
+/// What a [`SyncRequest`] syncs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum SyncRequestKind {
+    /// Sync a repository.
+    Repo,
+    /// Announce the node's inventory.
+    Inventory,
+}
+
+/// Format a count with its noun, e.g. `1 seed` or `3 seeds`.
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{count} {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Args)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SyncModeArgs {
     #[command(flatten)]
     directions: Directions,
     /// If `--inventory` is specified, the node's inventory is announced to the
-    /// network. This mode ignores the `--rid` argument.
+    /// network. This mode does not take a `--rid`.
     #[arg(long)]
     inventory: bool,
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Args)]
-#[group(required = false, multiple = true, conflicts_with = "inventory")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+// Conflicts with `--inventory` are reported by `Options::validate`, which can
+// explain them better than clap.
+#[group(required = false, multiple = true)]
 pub struct Directions {
     /// When `--fetch` is specified, any number of seeds may be given
-    /// using the `--seed` option, eg. `--seed <nid>@<addr>:<port>` [default: true].
-    #[arg(long, default_value_t = true)]
+    /// using the `--seed` option, eg. `--seed <nid>@<addr>:<port>`.
+    #[arg(long)]
     fetch: bool,
     /// When `--announce` is specified, this command will announce changes to
     /// the network. Can be used in tandem with `--fetch` to also fetch
-    /// beforehand [default: true].
-    #[arg(long, default_value_t = true)]
+    /// beforehand.
+    #[arg(long)]
     announce: bool,
+    /// Turn off fetching, only announcing changes to the network.
+    #[arg(long, conflicts_with = "fetch")]
+    no_fetch: bool,
+    /// Turn off announcing, only fetching changes from the network.
+    #[arg(long, conflicts_with = "announce")]
+    no_announce: bool,
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum SyncDirection {
     Fetch,
     Announce,
@@ -277,7 +1010,14 @@ pub enum SyncDirection {
 
 impl From<Directions> for SyncDirection {
     fn from(value: Directions) -> Self {
-        match (value.fetch, value.announce) {
+        // Naming a direction selects only that direction, otherwise both
+        // directions are on unless negated.
+        let selected = if value.fetch || value.announce {
+            (value.fetch, value.announce)
+        } else {
+            (!value.no_fetch, !value.no_announce)
+        };
+        match selected {
             (true, true) => SyncDirection::Both,
             (true, false) => SyncDirection::Fetch,
             (false, true) => SyncDirection::Announce,
@@ -287,108 +1027,4545 @@ impl From<Directions> for SyncDirection {
     }
 }
 
+impl fmt::Display for SyncDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fetch => write!(f, "fetch"),
+            Self::Announce => write!(f, "announce"),
+            Self::Both => write!(f, "fetch and announce"),
+        }
+    }
+}
+
+impl SyncDirection {
+    /// The timeout used when none is given explicitly. Announcing alone is
+    /// much quicker than fetching, so it gets a shorter default.
+    pub fn default_timeout(&self) -> time::Duration {
+        match self {
+            Self::Announce => DEFAULT_ANNOUNCE_TIMEOUT,
+            Self::Fetch | Self::Both => DEFAULT_SYNC_TIMEOUT,
+        }
+    }
+
+    /// The opposite direction: fetching becomes announcing and vice versa.
+    /// Syncing both ways has no meaningful inverse, so it gives `None`.
+    pub fn invert(&self) -> Option<SyncDirection> {
+        match self {
+            Self::Fetch => Some(Self::Announce),
+            Self::Announce => Some(Self::Fetch),
+            Self::Both => None,
+        }
+    }
+}
+
 /// Repository sync settings.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SyncSettings {
-    /// Sync with at least N replicas.
-    pub replicas: usize,
+    /// Sync with at least N replicas, or as many as possible.
+    pub replicas: ReplicaTarget,
+    /// Whether the replica target was asked for, rather than left at the
+    /// default. Only a target that was asked for has to be met by the seeds
+    /// given.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub explicit_replicas: bool,
     /// Sync with the given list of seeds.
-    pub seeds: Vec<NodeId>,
-    /// How long to wait for syncing to complete.
-    pub timeout: time::Duration,
+    pub seeds: Vec<Seed>,
+    /// Seeds to fall back to if the primary seeds do not meet the replica
+    /// target.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub fallback_seeds: Vec<Seed>,
+    /// How long to wait for syncing to complete. `None` to wait until the
+    /// sync completes, however long that takes.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_opt_secs"))]
+    pub timeout: Option<time::Duration>,
+    /// Shuffle the seeds deterministically using this value.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub shuffle_seed: Option<u64>,
+    /// After announcing, how long to wait for seeds to acknowledge that they
+    /// fetched. `None` if not waiting.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_opt_secs",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub ack_timeout: Option<time::Duration>,
+    /// The most bytes to fetch, or `None` for no limit.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_bytes: Option<u64>,
+}
+
+/// Serialize a duration as a number of seconds, e.g. `9`, with a fraction
+/// if it is not a whole number of seconds, e.g. `0.5`.
+#[cfg(feature = "serde")]
+fn serialize_secs<S: serde::Serializer>(
+    duration: &time::Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if duration.subsec_nanos() == 0 {
+        serializer.serialize_u64(duration.as_secs())
+    } else {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+}
+
+/// Serialize an optional duration as a number of seconds, see
+/// [`serialize_secs`].
+#[cfg(feature = "serde")]
+fn serialize_opt_secs<S: serde::Serializer>(
+    duration: &Option<time::Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serialize_secs(duration, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serialize an optional duration as a whole number of milliseconds.
+#[cfg(feature = "serde")]
+fn serialize_opt_millis<S: serde::Serializer>(
+    duration: &Option<time::Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer
+            .serialize_u64(u64::try_from(duration.as_millis()).map_err(serde::ser::Error::custom)?),
+        None => serializer.serialize_none(),
+    }
 }
 
 impl Default for SyncSettings {
     fn default() -> Self {
         Self {
-            replicas: 3,
+            replicas: ReplicaTarget::default(),
+            explicit_replicas: false,
             seeds: Vec::new(),
-            timeout: DEFAULT_SYNC_TIMEOUT,
+            fallback_seeds: Vec::new(),
+            timeout: Some(DEFAULT_SYNC_TIMEOUT),
+            shuffle_seed: None,
+            ack_timeout: None,
+            max_bytes: None,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Parser)]
-#[command(rename_all = "lowercase")]
-pub struct SyncSettingsArgs {
-    /// Sync with at least N replicas.
-    #[arg(long, short, default_value_t = 3, value_name = "count")]
-    pub replicas: usize,
-    /// Sync with the given list of seeds.
-    #[arg(long = "seed", action = clap::ArgAction::Append, value_name = "nid")]
-    pub seeds: Vec<NodeId>,
-    /// How long to wait for syncing to complete.
-    #[arg(long, value_name = "seconds", default_value_t = DEFAULT_SYNC_TIMEOUT.as_secs())]
-    pub timeout: u64,
-}
+impl SyncSettings {
+    /// Build settings in code, starting from the defaults.
+    ///
+    /// ```
+    /// use std::time;
+    /// use rad::{ReplicaTarget, Seed, SyncSettings};
+    ///
+    /// let seed: Seed = "z6MksFqXN3Yhqk8pTJdUGLwATkRfQvwZXPqR2qMEhbS9wzpT".parse()?;
+    /// let settings = SyncSettings::builder()
+    ///     .replicas(1)
+    ///     .seed(seed.clone())
+    ///     .seed(seed.clone())
+    ///     .timeout(time::Duration::from_secs(30))
+    ///     .build()?;
+    ///
+    /// assert_eq!(
+    ///     settings,
+    ///     SyncSettings {
+    ///         replicas: ReplicaTarget::Exact(1),
+    ///         explicit_replicas: true,
+    ///         seeds: vec![seed],
+    ///         timeout: Some(time::Duration::from_secs(30)),
+    ///         ..SyncSettings::default()
+    ///     }
+    /// );
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn builder() -> SyncSettingsBuilder {
+        SyncSettingsBuilder::default()
+    }
 
-impl From<SyncSettingsArgs> for SyncSettings {
-    fn from(s: SyncSettingsArgs) -> Self {
-        Self {
-            replicas: s.replicas,
-            seeds: s.seeds,
-            timeout: time::Duration::from_secs(s.timeout),
+    /// The default settings, overlaid with any values set through the
+    /// `RAD_SYNC_TIMEOUT`, `RAD_SYNC_REPLICAS` and `RAD_SYNC_SEEDS`
+    /// environment variables.
+    ///
+    /// Each variable is read as the flag it stands in for would read it, and
+    /// must pass the same checks: `RAD_SYNC_REPLICAS` may be `auto` or `max`,
+    /// `RAD_SYNC_SEEDS` is separated by commas and has repeats removed, and
+    /// `RAD_SYNC_TIMEOUT` must be above zero and at most [`MAX_SYNC_TIMEOUT`].
+    pub fn with_defaults_from_env() -> Result<Self, EnvError> {
+        let mut settings = Self::default();
+
+        if let Some(value) = env_var(ENV_SYNC_TIMEOUT)? {
+            let err = || EnvError::new(ENV_SYNC_TIMEOUT, value.clone());
+            settings.timeout = Some(parse_duration(&value).map_err(|_| err())?);
+            settings.validate_timeout().map_err(|_| err())?;
+        }
+        if let Some(value) = env_var(ENV_SYNC_REPLICAS)? {
+            let err = || EnvError::new(ENV_SYNC_REPLICAS, value.clone());
+            // Without `--network-size`, `auto` gives the default target.
+            settings.replicas = value.parse::<Replicas>().map_err(|_| err())?.target(None);
+            settings.explicit_replicas = true;
+            if settings.replicas == ReplicaTarget::Exact(0) {
+                return Err(err());
+            }
         }
+        if let Some(value) = env_var(ENV_SYNC_SEEDS)? {
+            // Split like `--seed`, whose values are separated by commas.
+            let seeds = value
+                .split(',')
+                .map(Seed::from_str)
+                .collect::<Result<_, _>>()
+                .map_err(|_| EnvError::new(ENV_SYNC_SEEDS, value.clone()))?;
+            settings.seeds = dedup_seeds(seeds);
+        }
+        Ok(settings)
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct RepoId(String);
+    /// The `rad sync` arguments that reproduce these settings when syncing
+    /// in the default direction, e.g. `["--replicas", "5", "--timeout",
+    /// "30s"]`. Arguments that match the defaults are omitted, so the
+    /// result assumes no `RAD_SYNC_*` environment variables are set.
+    ///
+    /// Seeds are given in sorted order, so the order they were given in is
+    /// not preserved. Without a shuffle seed that order is the dial order,
+    /// which the reproduced command may therefore not match.
+    pub fn to_args(&self) -> Vec<String> {
+        self.args_for(&SyncDirection::default())
+    }
 
-impl FromStr for RepoId {
-    type Err = Infallible;
+    /// Like [`SyncSettings::to_args`], with the default timeout of the given
+    /// direction.
+    fn args_for(&self, direction: &SyncDirection) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut arg = |flag: &str, value: Option<String>| {
+            args.push(flag.to_owned());
+            args.extend(value);
+        };
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+        if self.explicit_replicas || self.replicas != ReplicaTarget::default() {
+            arg("--replicas", Some(self.replicas.to_string()));
+        }
+        // Seeds are sorted so that the same settings always give the same
+        // arguments, whatever order the seeds were given in.
+        for seed in sorted_seeds(&self.seeds) {
+            arg("--seed", Some(seed));
+        }
+        for seed in sorted_seeds(&self.fallback_seeds) {
+            arg("--fallback-seed", Some(seed));
+        }
+        match self.timeout {
+            None => arg("--no-timeout", None),
+            Some(timeout) if timeout != direction.default_timeout() => {
+                arg("--timeout", Some(format_duration(timeout)))
+            }
+            Some(_) => {}
+        }
+        if let Some(shuffle_seed) = self.shuffle_seed {
+            arg("--shuffle-seed", Some(shuffle_seed.to_string()));
+        }
+        if let Some(ack_timeout) = self.ack_timeout {
+            arg("--wait-ack", None);
+            if ack_timeout != DEFAULT_ACK_TIMEOUT {
+                arg("--ack-timeout", Some(format_duration(ack_timeout)));
+            }
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            arg("--max-bytes", Some(max_bytes.to_string()));
+        }
+        args
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct NodeId(String);
+    /// The seeds in the order they should be dialed. Without a shuffle seed
+    /// this is the order they were given in.
+    pub fn effective_seeds(&self) -> Vec<Seed> {
+        let mut seeds = self.seeds.clone();
+        if let Some(seed) = self.shuffle_seed {
+            SplitMix64(seed).shuffle(&mut seeds);
+        }
+        seeds
+    }
 
-impl FromStr for NodeId {
-    type Err = Infallible;
+    /// The most bytes to fetch, or `None` for no limit.
+    pub fn max_bytes(&self) -> Option<u64> {
+        self.max_bytes
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+    /// Whether the replica target is met by the nodes that were contacted.
+    /// Only nodes that replicated successfully count, each distinct node
+    /// once, including explicit seeds.
+    pub fn replicas_satisfied(&self, contacted: &[(NodeId, SeedOutcome)]) -> bool {
+        let replicated = contacted
+            .iter()
+            .filter(|(_, outcome)| *outcome == SeedOutcome::Replicated)
+            .map(|(nid, _)| nid)
+            .collect::<HashSet<_>>();
+
+        match self.replicas {
+            ReplicaTarget::Exact(n) => replicated.len() >= n,
+            // There is always another seed to try.
+            ReplicaTarget::Max => false,
+        }
     }
-}
 
-/// Sync repositories to and from the network
-#[derive(Debug, Clone, PartialEq, Eq, Parser)]
-#[command(override_usage(SYNC_USAGE))]
-#[command(after_help = SYNC_HELP)]
-#[clap(help_template = HELP_TEMPLATE)]
-pub struct Sync {
-    /// Repository Identifier to be synchronized
-    #[arg(long, global = true, value_name = "rid")]
-    pub rid: Option<RepoId>,
-    /// Output debug information, if any
-    #[arg(long, global = true)]
-    pub debug: bool,
-    /// Out verbose information, if any
-    #[arg(long, short, global = true)]
-    pub verbose: bool,
-    #[command(flatten)]
-    pub sync: SyncModeArgs,
-    #[command(flatten)]
-    pub settings: SyncSettingsArgs,
-    #[command(subcommand)]
-    pub op: Option<Operation>,
-}
+    /// The fallback seeds to dial after the primary seeds were contacted,
+    /// in order. Empty if the primaries already meet the replica target;
+    /// otherwise every fallback that was not already contacted, to be
+    /// dialed until the target is met.
+    pub fn promote_fallbacks(&self, contacted: &[(NodeId, SeedOutcome)]) -> Vec<Seed> {
+        if self.replicas_satisfied(contacted) {
+            return Vec::new();
+        }
+        self.fallback_seeds
+            .iter()
+            .filter(|seed| !contacted.iter().any(|(nid, _)| *nid == seed.nid))
+            .cloned()
+            .collect()
+    }
 
-#[derive(Debug, Subcommand)]
-pub enum Commands {
-    Sync(Sync),
-}
+    /// Whether both settings sync with the same set of seeds, regardless of
+    /// the order or number of times they were given.
+    pub fn same_seeds_as(&self, other: &SyncSettings) -> bool {
+        let ours = self.seeds.iter().collect::<HashSet<_>>();
+        let theirs = other.seeds.iter().collect::<HashSet<_>>();
 
-/// Radicle command line interface
-#[derive(Debug, Parser)]
-#[clap(help_template = HELP_TEMPLATE)]
-#[command(version = "nix-0be7453")]
-#[command(propagate_version = true)]
+        ours == theirs
+    }
+
+    /// Cap the replica target at the number of seeds that are known or
+    /// resolvable, since syncing with more than that is not possible. An
+    /// unbounded target asks for exactly the seeds available.
+    pub fn cap_replicas(&self, available: usize) -> ReplicaCap {
+        let target = match self.replicas {
+            ReplicaTarget::Exact(n) => n,
+            ReplicaTarget::Max => available,
+        };
+        ReplicaCap {
+            target,
+            effective: target.min(available),
+        }
+    }
+
+    /// A warning if the replica target cannot be met by the seeds given,
+    /// see [`ReplicaCap::warning`]. Without seeds, the node picks from
+    /// every seed it knows of, and their number is not known here.
+    pub fn replica_warning(&self) -> Option<String> {
+        let available = self.seeds.len() + self.fallback_seeds.len();
+        if available == 0 {
+            return None;
+        }
+        self.cap_replicas(available).warning()
+    }
+
+    /// Check that the settings describe a sync that can be carried out: the
+    /// replica target and timeout are in range, no seed is given with two
+    /// different addresses, and the seeds given can meet a replica target
+    /// that was asked for.
+    ///
+    /// The default target need not be met by the seeds given, since the
+    /// node makes it up with seeds it knows of. Settings with a single seed
+    /// are therefore fine without `--replicas`.
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        self.validate_timeout()?;
+
+        if self.replicas == ReplicaTarget::Exact(0) {
+            return Err(SettingsError::ZeroReplicas);
+        }
+        let mut seeds = self.seeds.iter().chain(&self.fallback_seeds);
+        while let Some(seed) = seeds.next() {
+            if seeds.clone().any(|other| seed.conflicts_with(other)) {
+                return Err(SettingsError::ConflictingSeedAddress {
+                    nid: seed.nid.clone(),
+                });
+            }
+        }
+        if let ReplicaTarget::Exact(replicas) = self.replicas {
+            // Fallbacks can make up for primaries, so both count towards
+            // the seeds available.
+            let seeds = self.seeds.len() + self.fallback_seeds.len();
+            if self.explicit_replicas && seeds > 0 && replicas > seeds {
+                return Err(SettingsError::ReplicasExceedSeeds { replicas, seeds });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the timeout, if any, is above zero and at most
+    /// [`MAX_SYNC_TIMEOUT`].
+    fn validate_timeout(&self) -> Result<(), SettingsError> {
+        match self.timeout {
+            Some(timeout) if timeout.is_zero() => Err(SettingsError::ZeroTimeout),
+            Some(timeout) if timeout > MAX_SYNC_TIMEOUT => {
+                Err(SettingsError::TimeoutTooLong { timeout })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The seeds as strings, in sorted order.
+fn sorted_seeds(seeds: &[Seed]) -> Vec<String> {
+    let mut seeds = seeds.iter().map(Seed::to_string).collect::<Vec<_>>();
+    seeds.sort();
+    seeds
+}
+
+/// Parse a duration such as `30s`, `2m30s` or `500ms`. The units are `ms`,
+/// `s`, `m` and `h`, and a bare number is taken to be in seconds.
+pub fn parse_duration(s: &str) -> Result<time::Duration, ParseDurationError> {
+    let err = |span| ParseDurationError {
+        input: s.to_owned(),
+        span,
+    };
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(time::Duration::from_secs(secs));
+    }
+    if s.is_empty() {
+        return Err(err(None));
+    }
+
+    let mut total = time::Duration::ZERO;
+    let mut rest = s;
+    while !rest.is_empty() {
+        // Byte offset of the current `<value><unit>` component within `s`.
+        let start = s.len() - rest.len();
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| err(Some((start, s.len()))))?;
+        let unit = rest[digits..]
+            .find(|c: char| c.is_ascii_digit())
+            .map_or(rest.len(), |i| digits + i);
+        let span = Some((start, start + unit));
+        let value = rest[..digits].parse::<u64>().map_err(|_| err(span))?;
+        let duration = match &rest[digits..unit] {
+            "ms" => time::Duration::from_millis(value),
+            "s" => time::Duration::from_secs(value),
+            "m" => time::Duration::from_secs(value.checked_mul(60).ok_or_else(|| err(span))?),
+            "h" => time::Duration::from_secs(value.checked_mul(60 * 60).ok_or_else(|| err(span))?),
+            _ => return Err(err(span)),
+        };
+        total = total.checked_add(duration).ok_or_else(|| err(span))?;
+        rest = &rest[unit..];
+    }
+    Ok(total)
+}
+
+/// Format a duration the way [`parse_duration`] reads it, e.g. `2m30s`.
+pub fn format_duration(duration: time::Duration) -> String {
+    if duration.subsec_millis() != 0 {
+        return format!("{}ms", duration.as_millis());
+    }
+    let secs = duration.as_secs();
+    if secs == 0 {
+        return String::from("0s");
+    }
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    let mut out = String::new();
+    for (value, unit) in [(h, "h"), (m, "m"), (s, "s")] {
+        if value > 0 {
+            out.push_str(&format!("{value}{unit}"));
+        }
+    }
+    out
+}
+
+/// A string that is not a valid duration, see [`parse_duration`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDurationError {
+    /// The string that failed to parse.
+    pub input: String,
+    /// Byte range of the offending component within `input`, if any.
+    pub span: Option<(usize, usize)>,
+}
+
+impl ParseDurationError {
+    /// Byte range `(start, end)` of the offending part of the input.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        self.span
+    }
+}
+
+impl fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid duration `{}`, expected e.g. `30s`, `2m30s` or `500ms`",
+            self.input
+        )
+    }
+}
+
+impl error::Error for ParseDurationError {}
+
+/// Parse a size such as `100MB` or `1GiB` into a number of bytes. Decimal
+/// (`kB`, `MB`, `GB`, `TB`) and binary (`KiB`, `MiB`, `GiB`, `TiB`) units
+/// are supported, and a bare number is taken to be in bytes.
+pub fn parse_size(s: &str) -> Result<u64, ParseSizeError> {
+    let err = || ParseSizeError {
+        input: s.to_owned(),
+    };
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let value = s[..digits].parse::<u64>().map_err(|_| err())?;
+    let unit: u64 = match s[digits..].trim_start() {
+        "" | "B" => 1,
+        "kB" | "KB" => 1000,
+        "MB" => 1000u64.pow(2),
+        "GB" => 1000u64.pow(3),
+        "TB" => 1000u64.pow(4),
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        _ => return Err(err()),
+    };
+    value.checked_mul(unit).ok_or_else(err)
+}
+
+/// A string that is not a valid size, see [`parse_size`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSizeError {
+    /// The string that failed to parse.
+    pub input: String,
+}
+
+impl fmt::Display for ParseSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid size `{}`, expected e.g. `500kB`, `100MB` or `1GiB`",
+            self.input
+        )
+    }
+}
+
+impl error::Error for ParseSizeError {}
+
+/// A small seedable pseudo-random number generator, used where the same input
+/// must always produce the same output.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Fisher-Yates shuffle.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, which is stable across runs and
+/// platforms, unlike the standard library's hashers.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+/// The value of an environment variable as an argument default, or no
+/// default if it is unset.
+fn env_default(var: &str) -> Resettable<OsStr> {
+    env::var(var).ok().map(OsStr::from).into()
+}
+
+/// Read an environment variable, treating an unset variable as absent.
+fn env_var(var: &'static str) -> Result<Option<String>, EnvError> {
+    match env::var(var) {
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(value)) => {
+            Err(EnvError::new(var, value.to_string_lossy().into_owned()))
+        }
+    }
+}
+
+/// An environment variable holds a value that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvError {
+    /// The name of the environment variable.
+    pub var: &'static str,
+    /// The value that failed to parse.
+    pub value: String,
+}
+
+impl EnvError {
+    fn new(var: &'static str, value: String) -> Self {
+        Self { var, value }
+    }
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid value `{}` for `{}`", self.value, self.var)
+    }
+}
+
+impl error::Error for EnvError {}
+
+/// Sync defaults read from a TOML config file.
+///
+/// Settings are taken from, in order of precedence: flags, the `RAD_SYNC_*`
+/// environment variables, this file, and the built-in defaults. The values
+/// in this file become the defaults of the flags, so any flag that is given
+/// overrides them, and each value is skipped if its environment variable is
+/// set.
+///
+/// ```toml
+/// replicas = "auto"
+/// timeout = "30s"
+/// seeds = ["z6MksFqXN3Yhqk8pTJdUGLwATkRfQvwZXPqR2qMEhbS9wzpT"]
+/// sort_by = "alias"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct SyncConfig {
+    /// Sync with at least N replicas, as a number, `"auto"` or `"max"`.
+    pub replicas: Option<Replicas>,
+    /// How long to wait for syncing to complete, as a duration such as
+    /// `"30s"` or a number of seconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "deserialize_opt_duration")
+    )]
+    pub timeout: Option<time::Duration>,
+    /// Sync with the given list of seeds.
+    pub seeds: Option<Vec<Seed>>,
+    /// The field to sort the status table by.
+    pub sort_by: Option<SortBy>,
+}
+
+impl SyncConfig {
+    /// Read the config from the given TOML file.
+    #[cfg(feature = "serde")]
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|err| ConfigError::Io {
+            path: path.to_path_buf(),
+            err,
+        })?;
+        toml::from_str(&contents).map_err(|err| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message: err.message().to_owned(),
+        })
+    }
+
+    /// Read the config from the given TOML file.
+    #[cfg(not(feature = "serde"))]
+    pub fn load(_path: &Path) -> Result<Self, ConfigError> {
+        Err(ConfigError::Unsupported)
+    }
+
+    /// The config file read when no `--config` is given:
+    /// [`CONFIG_FILE`] in `$RAD_HOME`, or in `~/.radicle` if it is unset.
+    pub fn default_path() -> Option<PathBuf> {
+        let home = match env::var_os(ENV_RAD_HOME) {
+            Some(home) => PathBuf::from(home),
+            None => PathBuf::from(env::var_os("HOME")?).join(".radicle"),
+        };
+        Some(home.join(CONFIG_FILE))
+    }
+
+    /// Use the configured values as the defaults of the `rad` command, in
+    /// the order of precedence described on [`SyncConfig`].
+    fn apply(self, cmd: clap::Command) -> clap::Command {
+        let unset = |var| env::var_os(var).is_none();
+
+        cmd.mut_subcommand("sync", |mut sync| {
+            if let (Some(replicas), true) = (self.replicas, unset(ENV_SYNC_REPLICAS)) {
+                sync = sync.mut_arg("replicas", |arg| arg.default_value(replicas.to_string()));
+            }
+            if let (Some(timeout), true) = (self.timeout, unset(ENV_SYNC_TIMEOUT)) {
+                sync = sync.mut_arg("timeout", |arg| arg.default_value(format_duration(timeout)));
+            }
+            if let (Some(seeds), true) = (self.seeds, unset(ENV_SYNC_SEEDS)) {
+                sync = sync.mut_arg("seeds", |arg| {
+                    arg.default_values(seeds.iter().map(Seed::to_string))
+                });
+            }
+            if let Some(sort_by) = self.sort_by.and_then(|s| s.to_possible_value()) {
+                sync = sync.mut_subcommand("status", |status| {
+                    status.mut_arg("sort_by", |arg| {
+                        arg.default_value(sort_by.get_name().to_owned())
+                    })
+                });
+            }
+            sync
+        })
+    }
+}
+
+/// Deserialize an optional duration given either as a string such as `"30s"`
+/// or as a number of seconds.
+#[cfg(feature = "serde")]
+fn deserialize_opt_duration<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<time::Duration>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Duration {
+        Secs(u64),
+        Str(String),
+    }
+
+    match <Duration as serde::Deserialize>::deserialize(deserializer)? {
+        Duration::Secs(secs) => Ok(Some(time::Duration::from_secs(secs))),
+        Duration::Str(s) => parse_duration(&s)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// A config file could not be read.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io { path: PathBuf, err: io::Error },
+    /// The file is not valid TOML, or has unknown or invalid keys.
+    Parse { path: PathBuf, message: String },
+    /// Config files require the `serde` feature.
+    Unsupported,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, err } => {
+                write!(f, "failed to read config `{}`: {err}", path.display())
+            }
+            Self::Parse { path, message } => {
+                write!(
+                    f,
+                    "invalid config `{}`: {}",
+                    path.display(),
+                    message.trim_end()
+                )
+            }
+            Self::Unsupported => write!(f, "`--config` requires the `serde` feature"),
+        }
+    }
+}
+
+impl error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { err, .. } => Some(err),
+            Self::Parse { .. } | Self::Unsupported => None,
+        }
+    }
+}
+
+/// Remove repeated seeds, keeping the order in which each node was first
+/// given. Seeds are the same if their Node IDs are, and a seed with an address
+/// replaces an earlier one without. Seeds with conflicting addresses are kept,
+/// for [`SyncSettings::validate`] to reject.
+fn dedup_seeds(seeds: Vec<Seed>) -> Vec<Seed> {
+    let mut deduped: Vec<Seed> = Vec::with_capacity(seeds.len());
+
+    for seed in seeds {
+        match deduped
+            .iter_mut()
+            .find(|s| s.nid == seed.nid && !s.conflicts_with(&seed))
+        {
+            Some(existing) => {
+                if existing.addr.is_none() {
+                    existing.addr = seed.addr;
+                }
+            }
+            None => deduped.push(seed),
+        }
+    }
+    deduped
+}
+
+/// The outcome of contacting a node during a sync.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedOutcome {
+    /// The node replicated successfully.
+    Replicated,
+    /// The node was contacted, but replication failed.
+    Failed,
+}
+
+/// Builder for [`SyncSettings`], see [`SyncSettings::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct SyncSettingsBuilder {
+    settings: SyncSettings,
+}
+
+impl SyncSettingsBuilder {
+    /// Sync with at least N replicas, or as many as possible.
+    pub fn replicas(mut self, replicas: impl Into<ReplicaTarget>) -> Self {
+        self.settings.replicas = replicas.into();
+        self.settings.explicit_replicas = true;
+        self
+    }
+
+    /// Add a seed to sync with.
+    pub fn seed(mut self, seed: impl Into<Seed>) -> Self {
+        self.settings.seeds.push(seed.into());
+        self
+    }
+
+    /// Add seeds to sync with.
+    pub fn seeds<S: Into<Seed>>(mut self, seeds: impl IntoIterator<Item = S>) -> Self {
+        self.settings
+            .seeds
+            .extend(seeds.into_iter().map(Into::into));
+        self
+    }
+
+    /// How long to wait for syncing to complete.
+    pub fn timeout(mut self, timeout: time::Duration) -> Self {
+        self.settings.timeout = Some(timeout);
+        self
+    }
+
+    /// Wait for syncing to complete, however long that takes.
+    pub fn no_timeout(mut self) -> Self {
+        self.settings.timeout = None;
+        self
+    }
+
+    /// Build the settings, dropping repeated seeds by Node ID.
+    pub fn build(self) -> Result<SyncSettings, SettingsError> {
+        let mut settings = self.settings;
+        settings.validate_timeout()?;
+        settings.seeds = dedup_seeds(settings.seeds);
+
+        Ok(settings)
+    }
+}
+
+/// A replica target capped at the number of available seeds, see
+/// [`SyncSettings::cap_replicas`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplicaCap {
+    /// The replica target that was asked for.
+    pub target: usize,
+    /// The number of replicas that can actually be synced with.
+    pub effective: usize,
+}
+
+impl ReplicaCap {
+    /// Whether the target asks for more replicas than there are seeds.
+    pub fn is_oversubscribed(&self) -> bool {
+        self.effective < self.target
+    }
+
+    /// A warning to show under `--verbose` if the target cannot be met.
+    pub fn warning(&self) -> Option<String> {
+        self.is_oversubscribed().then(|| {
+            format!(
+                "replica target of {} cannot be met, only {} seed(s) are available",
+                self.target, self.effective
+            )
+        })
+    }
+}
+
+/// An invalid combination of [`SyncSettings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsError {
+    /// The replica target is zero.
+    ZeroReplicas,
+    /// The timeout is zero.
+    ZeroTimeout,
+    /// The timeout is longer than [`MAX_SYNC_TIMEOUT`].
+    TimeoutTooLong { timeout: time::Duration },
+    /// Explicit seeds were given, but fewer than an explicit replica target.
+    ReplicasExceedSeeds { replicas: usize, seeds: usize },
+    /// The same seed was given with two different addresses.
+    ConflictingSeedAddress { nid: NodeId },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroReplicas => write!(f, "replica count must be at least 1"),
+            Self::ZeroTimeout => write!(f, "timeout must be greater than zero"),
+            Self::TimeoutTooLong { timeout } => write!(
+                f,
+                "timeout `{}` exceeds the maximum of `{}`, use `--no-timeout` to wait \
+                 indefinitely",
+                format_duration(*timeout),
+                format_duration(MAX_SYNC_TIMEOUT)
+            ),
+            Self::ReplicasExceedSeeds { replicas, seeds } => write!(
+                f,
+                "replica count {replicas} cannot be met by the {seeds} seed(s) given, \
+                 lower `--replicas` or add more seeds"
+            ),
+            Self::ConflictingSeedAddress { nid } => {
+                write!(f, "seed `{nid}` is given with two different addresses")
+            }
+        }
+    }
+}
+
+impl error::Error for SettingsError {}
+
+/// The number of replicas to sync with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReplicaTarget {
+    /// Sync with at least this many replicas.
+    Exact(usize),
+    /// Sync with as many seeds as are available.
+    Max,
+}
+
+impl ReplicaTarget {
+    /// Whether the target is as many replicas as possible, rather than a
+    /// fixed count.
+    pub fn is_unbounded(&self) -> bool {
+        matches!(self, Self::Max)
+    }
+}
+
+impl Default for ReplicaTarget {
+    fn default() -> Self {
+        Self::Exact(DEFAULT_REPLICAS)
+    }
+}
+
+impl From<usize> for ReplicaTarget {
+    fn from(n: usize) -> Self {
+        Self::Exact(n)
+    }
+}
+
+impl FromStr for ReplicaTarget {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "max" => Ok(Self::Max),
+            _ => s.parse().map(Self::Exact),
+        }
+    }
+}
+
+impl fmt::Display for ReplicaTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(n) => write!(f, "{n}"),
+            Self::Max => write!(f, "max"),
+        }
+    }
+}
+
+/// Serialized as a number, or as `"max"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ReplicaTarget {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Exact(n) => serializer.serialize_u64(*n as u64),
+            Self::Max => serializer.serialize_str("max"),
+        }
+    }
+}
+
+/// The replica count given by `--replicas`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Replicas {
+    /// Sync with exactly this many replicas.
+    Count(usize),
+    /// Derive the replica count from the network size, see
+    /// [`replicas_for_network_size`].
+    Auto,
+    /// Sync with as many seeds as are available.
+    Max,
+}
+
+impl Replicas {
+    /// The replica target, deriving it from the network size for `auto`, or
+    /// using [`DEFAULT_REPLICAS`] if the network size is not known.
+    pub fn target(self, network_size: Option<usize>) -> ReplicaTarget {
+        match self {
+            Self::Count(n) => ReplicaTarget::Exact(n),
+            Self::Auto => ReplicaTarget::Exact(
+                network_size
+                    .map(replicas_for_network_size)
+                    .unwrap_or(DEFAULT_REPLICAS),
+            ),
+            Self::Max => ReplicaTarget::Max,
+        }
+    }
+}
+
+impl FromStr for Replicas {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "max" => Ok(Self::Max),
+            _ => s.parse().map(Self::Count),
+        }
+    }
+}
+
+/// Serialized as a number, or as `"auto"` or `"max"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Replicas {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Count(n) => serializer.serialize_u64(*n as u64),
+            Self::Auto => serializer.serialize_str("auto"),
+            Self::Max => serializer.serialize_str("max"),
+        }
+    }
+}
+
+/// Deserialized from a number, or from `"auto"` or `"max"`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Replicas {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Replicas {
+            Count(usize),
+            Str(String),
+        }
+
+        match <Replicas as serde::Deserialize>::deserialize(deserializer)? {
+            Replicas::Count(n) => Ok(Self::Count(n)),
+            Replicas::Str(s) => s.parse().map_err(|_| {
+                serde::de::Error::invalid_value(
+                    serde::de::Unexpected::Str(&s),
+                    &"a number, `auto` or `max`",
+                )
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Replicas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Count(n) => write!(f, "{n}"),
+            Self::Auto => write!(f, "auto"),
+            Self::Max => write!(f, "max"),
+        }
+    }
+}
+
+/// The replica count used by `--replicas auto` for a network of `size` seeds.
+///
+/// This is the square root of the network size, rounded up, and at least 1:
+/// a network of 1 seed gives 1 replica, 10 seeds give 4, 100 seeds give 10,
+/// and 1000 seeds give 32. Without a network size, [`DEFAULT_REPLICAS`] is
+/// used instead.
+pub fn replicas_for_network_size(size: usize) -> usize {
+    let root = size.isqrt();
+    let root = if root * root < size { root + 1 } else { root };
+
+    root.max(1)
+}
+
+/// A peer known to the local node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownPeer {
+    /// The peer's Node ID.
+    pub nid: NodeId,
+    /// The peer's alias, if it has one.
+    pub alias: Option<String>,
+}
+
+impl Peer for KnownPeer {
+    fn nid(&self) -> &NodeId {
+        &self.nid
+    }
+
+    fn alias(&self) -> Option<&str> {
+        self.alias.as_deref()
+    }
+}
+
+/// A source of the peers known to the local node.
+pub trait KnownPeers {
+    /// The known peers, in the order they should be tried.
+    fn known_peers(&self) -> Vec<KnownPeer>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Parser)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[command(rename_all = "lowercase")]
+pub struct SyncSettingsArgs {
+    /// Sync with at least N replicas, `max` to sync with as many seeds as
+    /// are available, or `auto` to derive N from `--network-size`. N must be
+    /// at least 1 [default: 3].
+    #[arg(long, short, value_name = "count", env = ENV_SYNC_REPLICAS)]
+    pub replicas: Option<Replicas>,
+    /// The approximate number of seeds in the network, used by `--replicas auto`.
+    #[arg(long = "network-size", value_name = "n")]
+    pub network_size: Option<usize>,
+    /// Sync with the given list of seeds, each as `<nid>` or
+    /// `<nid>@<host>:<port>`.
+    #[arg(
+        long = "seed",
+        action = clap::ArgAction::Append,
+        value_name = "nid",
+        value_delimiter = ',',
+        env = ENV_SYNC_SEEDS
+    )]
+    pub seeds: Vec<Seed>,
+    /// Fall back to the given seeds if the primary seeds do not meet the
+    /// replica target.
+    #[arg(
+        long = "fallback-seed",
+        action = clap::ArgAction::Append,
+        value_name = "nid",
+        value_delimiter = ','
+    )]
+    pub fallback_seeds: Vec<Seed>,
+    /// Substitute `${VAR}` placeholders in `--seed` and `--fallback-seed`
+    /// with the values of environment variables.
+    #[arg(long = "expand-env")]
+    pub expand_env: bool,
+    /// Whether the seeds were parsed with `${VAR}` placeholders expanded.
+    /// Only [`Options::try_parse_with_config_from`] does so.
+    #[arg(skip)]
+    pub env_expanded: bool,
+    /// How long to wait for syncing to complete, e.g. `30s`, `2m30s` or
+    /// `500ms`, and at most `1h` [default: 9s, or 3s when only announcing]
+    /// [env: RAD_SYNC_TIMEOUT].
+    // The environment variable is read as a default rather than through
+    // `env`, so that `--no-timeout` can override it without a conflict.
+    #[arg(
+        long,
+        value_name = "duration",
+        value_parser = parse_duration,
+        default_value = env_default(ENV_SYNC_TIMEOUT)
+    )]
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_opt_secs"))]
+    pub timeout: Option<time::Duration>,
+    /// Wait for syncing to complete, however long that takes.
+    #[arg(long = "no-timeout", conflicts_with = "timeout")]
+    pub no_timeout: bool,
+    /// Also sync with the peers known to the local node.
+    #[arg(long = "include-known")]
+    pub include_known: bool,
+    /// Only include known peers whose alias contains the given text.
+    #[arg(
+        long = "known-filter",
+        value_name = "alias",
+        requires = "include_known"
+    )]
+    pub known_filter: Option<String>,
+    /// Shuffle the seeds deterministically using the given value.
+    #[arg(long = "shuffle-seed", value_name = "value")]
+    pub shuffle_seed: Option<u64>,
+    /// After announcing, wait for seeds to acknowledge that they fetched.
+    #[arg(long = "wait-ack")]
+    pub wait_ack: bool,
+    /// How long to wait for acknowledgements after announcing.
+    #[arg(
+        long = "ack-timeout",
+        value_name = "duration",
+        value_parser = parse_duration,
+        default_value = format_duration(DEFAULT_ACK_TIMEOUT),
+        requires = "wait_ack"
+    )]
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_secs"))]
+    pub ack_timeout: time::Duration,
+    /// The most bytes to fetch, e.g. `100MB` or `1GiB`.
+    #[arg(long = "max-bytes", value_name = "size", value_parser = parse_size)]
+    pub max_bytes: Option<u64>,
+}
+
+impl SyncSettingsArgs {
+    /// Merge the peers from `known` into the seed list if `--include-known`
+    /// was given, keeping only those matching `--known-filter`.
+    pub fn include_known(&mut self, known: &impl KnownPeers) {
+        if !self.include_known {
+            return;
+        }
+        for peer in known.known_peers() {
+            let matches = match &self.known_filter {
+                Some(filter) => peer
+                    .alias
+                    .as_deref()
+                    .is_some_and(|alias| alias.contains(filter.as_str())),
+                None => true,
+            };
+            if matches && !self.seeds.iter().any(|seed| seed.nid == peer.nid) {
+                self.seeds.push(Seed::from(peer.nid));
+            }
+        }
+    }
+
+    /// Resolve the settings for the given direction, falling back to the
+    /// direction's default timeout if `--timeout` was not given.
+    pub fn resolve(self, direction: &SyncDirection) -> SyncSettings {
+        let seeds = dedup_seeds(self.seeds);
+        // A seed given as both primary and fallback is a primary, unless the
+        // two disagree on its address.
+        let fallback_seeds = dedup_seeds(self.fallback_seeds)
+            .into_iter()
+            .filter(|fallback| {
+                !seeds
+                    .iter()
+                    .any(|seed| seed.nid == fallback.nid && !seed.conflicts_with(fallback))
+            })
+            .collect();
+
+        SyncSettings {
+            replicas: self
+                .replicas
+                .unwrap_or(Replicas::Count(DEFAULT_REPLICAS))
+                .target(self.network_size),
+            explicit_replicas: self.replicas.is_some(),
+            seeds,
+            fallback_seeds,
+            timeout: if self.no_timeout {
+                None
+            } else {
+                Some(self.timeout.unwrap_or_else(|| direction.default_timeout()))
+            },
+            shuffle_seed: self.shuffle_seed,
+            // Acknowledgements only follow an announcement.
+            ack_timeout: match direction {
+                SyncDirection::Announce | SyncDirection::Both if self.wait_ack => {
+                    Some(self.ack_timeout)
+                }
+                _ => None,
+            },
+            max_bytes: self.max_bytes,
+        }
+    }
+}
+
+impl From<SyncSettingsArgs> for SyncSettings {
+    fn from(s: SyncSettingsArgs) -> Self {
+        s.resolve(&SyncDirection::default())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct RepoId(String);
+
+impl Borrow<str> for RepoId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for RepoId {
+    type Err = ParseRepoIdError;
+
+    /// Parse a RID such as `rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5`. The `rad:`
+    /// prefix may be left out, and is added if it is.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.strip_prefix(RepoId::SCHEME).unwrap_or(s);
+        if id.is_empty() {
+            return Err(ParseRepoIdError::Empty);
+        }
+        let Some(payload) = id.strip_prefix(NodeId::PREFIX) else {
+            return Err(ParseRepoIdError::InvalidPrefix);
+        };
+        if payload.is_empty() {
+            return Err(ParseRepoIdError::Empty);
+        }
+        if let Some(c) = payload.chars().find(|c| !BASE58_ALPHABET.contains(*c)) {
+            return Err(ParseRepoIdError::InvalidCharacter(c));
+        }
+        Ok(Self(format!("{}{id}", RepoId::SCHEME)))
+    }
+}
+
+impl RepoId {
+    /// The URN scheme that RIDs are prefixed with.
+    const SCHEME: &'static str = "rad:";
+
+    /// The canonical form of the RID, including the `rad:` prefix.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The bytes encoded by the RID, without the `rad:` and multibase
+    /// prefixes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // The payload is checked to be base58 when parsing.
+        decode_base58(&self.0[RepoId::SCHEME.len() + NodeId::PREFIX.len_utf8()..])
+    }
+}
+
+impl fmt::Display for RepoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A string that is not a valid [`RepoId`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRepoIdError {
+    /// The RID is empty.
+    Empty,
+    /// The RID does not start with `rad:z` or `z`.
+    InvalidPrefix,
+    /// The RID contains a character outside the base58btc alphabet.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for ParseRepoIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty RID"),
+            Self::InvalidPrefix => write!(
+                f,
+                "invalid RID, expected it to start with `{}{}`",
+                RepoId::SCHEME,
+                NodeId::PREFIX
+            ),
+            Self::InvalidCharacter(c) => {
+                write!(f, "invalid character `{c}` in RID, expected base58")
+            }
+        }
+    }
+}
+
+impl error::Error for ParseRepoIdError {}
+
+/// Decode a base58btc string. Every character must be in [`BASE58_ALPHABET`].
+fn decode_base58(s: &str) -> Vec<u8> {
+    // Big-endian bytes of the number, built up one digit at a time.
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in s.chars() {
+        let mut carry = BASE58_ALPHABET.find(c).unwrap_or_default() as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    // Each leading `1` encodes a leading zero byte.
+    let zeros = s.chars().take_while(|c| *c == '1').count();
+    let mut decoded = vec![0; zeros];
+    decoded.extend(bytes);
+    decoded
+}
+
+/// Node IDs are ordered by their encoded string.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct NodeId(String);
+
+impl Borrow<str> for NodeId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for NodeId {
+    type Err = ParseNodeIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(key) = s.strip_prefix(NodeId::PREFIX) else {
+            return Err(ParseNodeIdError::InvalidPrefix);
+        };
+        if s.len() != NodeId::LENGTH {
+            return Err(ParseNodeIdError::InvalidLength(s.len()));
+        }
+        if let Some(c) = key.chars().find(|c| !BASE58_ALPHABET.contains(*c)) {
+            return Err(ParseNodeIdError::InvalidCharacter(c));
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl NodeId {
+    /// The multibase prefix for base58btc.
+    const PREFIX: char = 'z';
+    /// The length of an encoded Ed25519 public key, including the prefix.
+    const LENGTH: usize = 48;
+}
+
+/// The characters of the base58btc alphabet.
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// A string that is not a valid [`NodeId`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseNodeIdError {
+    /// The Node ID does not start with the base58btc prefix `z`.
+    InvalidPrefix,
+    /// The Node ID is not the length of an encoded Ed25519 key.
+    InvalidLength(usize),
+    /// The Node ID contains a character outside the base58btc alphabet.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for ParseNodeIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPrefix => write!(
+                f,
+                "invalid Node ID, expected it to start with `{}`",
+                NodeId::PREFIX
+            ),
+            Self::InvalidLength(len) => write!(
+                f,
+                "invalid Node ID length {len}, expected {}",
+                NodeId::LENGTH
+            ),
+            Self::InvalidCharacter(c) => {
+                write!(f, "invalid character `{c}` in Node ID, expected base58")
+            }
+        }
+    }
+}
+
+impl error::Error for ParseNodeIdError {}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A seed to sync with, given as `<nid>` or `<nid>@<host>:<port>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Seed {
+    /// The seed's Node ID.
+    pub nid: NodeId,
+    /// The address to connect to the seed on, if given.
+    pub addr: Option<Address>,
+}
+
+impl Seed {
+    /// Whether both seeds are the same node, given with different addresses.
+    pub fn conflicts_with(&self, other: &Seed) -> bool {
+        self.nid == other.nid && matches!((&self.addr, &other.addr), (Some(a), Some(b)) if a != b)
+    }
+}
+
+impl From<NodeId> for Seed {
+    fn from(nid: NodeId) -> Self {
+        Self { nid, addr: None }
+    }
+}
+
+impl FromStr for Seed {
+    type Err = ParseSeedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('@') {
+            Some((nid, addr)) => Ok(Self {
+                nid: ParseSeedError::parse_nid(nid)?,
+                addr: Some(
+                    addr.parse()
+                        .map_err(|e: ParseSeedError| e.shift(nid.len() + 1))?,
+                ),
+            }),
+            None => Ok(Self {
+                nid: ParseSeedError::parse_nid(s)?,
+                addr: None,
+            }),
+        }
+    }
+}
+
+impl Seed {
+    /// Parse a seed after substituting `${VAR}` placeholders with the values
+    /// of the environment variables, as done by `--expand-env`. Error spans
+    /// refer to the expanded string.
+    pub fn parse_expanding_env(s: &str) -> Result<Self, ParseSeedError> {
+        expand_env(s)?.parse()
+    }
+}
+
+/// Substitute each `${VAR}` in `s` with the value of the environment
+/// variable `VAR`, which must be set.
+pub fn expand_env(s: &str) -> Result<String, ExpandEnvError> {
+    let mut expanded = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return Err(ExpandEnvError::Unclosed);
+        };
+        let var = &rest[start + 2..start + end];
+        match env::var(var) {
+            Ok(value) => expanded.push_str(&value),
+            Err(_) => {
+                return Err(ExpandEnvError::Unset {
+                    var: var.to_owned(),
+                })
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+/// A `${VAR}` placeholder could not be expanded, see [`expand_env`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpandEnvError {
+    /// The environment variable is not set, or is not valid unicode.
+    Unset { var: String },
+    /// A `${` has no closing `}`.
+    Unclosed,
+}
+
+impl fmt::Display for ExpandEnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unset { var } => write!(f, "environment variable `{var}` is not set"),
+            Self::Unclosed => write!(f, "unclosed `${{` in placeholder"),
+        }
+    }
+}
+
+impl error::Error for ExpandEnvError {}
+
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.addr {
+            Some(addr) => write!(f, "{}@{addr}", self.nid),
+            None => write!(f, "{}", self.nid),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Seed {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Seed {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The address of a seed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Address {
+    pub host: Host,
+    pub port: u16,
+}
+
+/// The host part of an [`Address`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Host {
+    /// An IPv4 or IPv6 address.
+    Ip(IpAddr),
+    /// A domain name.
+    Dns(String),
+}
+
+impl FromStr for Address {
+    type Err = ParseSeedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, port) = if let Some(rest) = s.strip_prefix('[') {
+            // IPv6 addresses are bracketed to separate them from the port.
+            let (ip, port) = rest
+                .split_once(']')
+                .ok_or_else(|| ParseSeedError::InvalidHost {
+                    host: s.to_owned(),
+                    offset: 0,
+                })?;
+            let ip = ip
+                .parse::<Ipv6Addr>()
+                .map_err(|_| ParseSeedError::InvalidHost {
+                    host: ip.to_owned(),
+                    offset: 1,
+                })?;
+            let port = port.strip_prefix(':').ok_or(ParseSeedError::MissingPort)?;
+
+            (Host::Ip(IpAddr::V6(ip)), port)
+        } else {
+            let (host, port) = s.rsplit_once(':').ok_or(ParseSeedError::MissingPort)?;
+            let host = match host.parse::<Ipv4Addr>() {
+                Ok(ip) => Host::Ip(IpAddr::V4(ip)),
+                Err(_) if is_domain(host) => Host::Dns(host.to_owned()),
+                Err(_) => {
+                    return Err(ParseSeedError::InvalidHost {
+                        host: host.to_owned(),
+                        offset: 0,
+                    })
+                }
+            };
+            (host, port)
+        };
+        let port = port.parse().map_err(|_| ParseSeedError::InvalidPort {
+            port: port.to_owned(),
+            offset: s.len() - port.len(),
+        })?;
+
+        Ok(Self { host, port })
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.host {
+            Host::Ip(IpAddr::V6(ip)) => write!(f, "[{ip}]:{}", self.port),
+            Host::Ip(IpAddr::V4(ip)) => write!(f, "{ip}:{}", self.port),
+            Host::Dns(name) => write!(f, "{name}:{}", self.port),
+        }
+    }
+}
+
+/// Whether `s` is a plausible domain name: dot-separated labels of letters,
+/// digits and hyphens.
+fn is_domain(s: &str) -> bool {
+    !s.is_empty()
+        && s.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// A string that is not a valid [`Seed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSeedError {
+    /// The Node ID part is invalid.
+    InvalidNodeId {
+        /// The offending Node ID, always at the start of the parsed input.
+        nid: String,
+        /// Why the Node ID is invalid.
+        err: ParseNodeIdError,
+    },
+    /// The address has no `:<port>`.
+    MissingPort,
+    /// The port is not a number between 0 and 65535.
+    InvalidPort {
+        /// The offending port.
+        port: String,
+        /// Byte offset of the port within the parsed input.
+        offset: usize,
+    },
+    /// The host is neither an IP address nor a domain name.
+    InvalidHost {
+        /// The offending host.
+        host: String,
+        /// Byte offset of the host within the parsed input.
+        offset: usize,
+    },
+    /// A `${VAR}` placeholder could not be expanded.
+    Env(ExpandEnvError),
+}
+
+impl ParseSeedError {
+    /// Byte range `(start, end)` of the offending part of the input.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::InvalidNodeId { nid, .. } => Some((0, nid.len())),
+            Self::MissingPort | Self::Env(_) => None,
+            Self::InvalidPort { port, offset } => Some((*offset, offset + port.len())),
+            Self::InvalidHost { host, offset } => Some((*offset, offset + host.len())),
+        }
+    }
+
+    /// Shift the reported offset by `by` bytes, for errors in a suffix of the input.
+    fn shift(mut self, by: usize) -> Self {
+        if let Self::InvalidPort { offset, .. } | Self::InvalidHost { offset, .. } = &mut self {
+            *offset += by;
+        }
+        self
+    }
+
+    /// Parse the Node ID part of a seed.
+    fn parse_nid(nid: &str) -> Result<NodeId, Self> {
+        nid.parse().map_err(|err| Self::InvalidNodeId {
+            nid: nid.to_owned(),
+            err,
+        })
+    }
+}
+
+impl From<ExpandEnvError> for ParseSeedError {
+    fn from(err: ExpandEnvError) -> Self {
+        Self::Env(err)
+    }
+}
+
+impl fmt::Display for ParseSeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNodeId { err, .. } => write!(f, "{err}"),
+            Self::MissingPort => write!(f, "missing port, expected `<nid>@<host>:<port>`"),
+            Self::InvalidPort { port, .. } => write!(f, "invalid port `{port}`"),
+            Self::InvalidHost { host, .. } => write!(f, "invalid host `{host}`"),
+            Self::Env(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl error::Error for ParseSeedError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::InvalidNodeId { err, .. } => Some(err),
+            Self::Env(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Resolves the repository in a working directory, e.g. by reading its
+/// Radicle metadata.
+pub trait RepoResolver {
+    /// The repository in the working directory at `path`.
+    fn resolve(&self, path: &Path) -> Result<RepoId, NotARepository>;
+}
+
+/// The directory given by `--repo-path` is not a Radicle repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotARepository {
+    /// The directory that was resolved.
+    pub path: PathBuf,
+}
+
+impl fmt::Display for NotARepository {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a Radicle repository", self.path.display())
+    }
+}
+
+impl error::Error for NotARepository {}
+
+/// The repository to sync could not be resolved, see [`Options::resolve_rid`].
+#[derive(Debug)]
+pub enum RidError {
+    /// No repository was given, and `cwd` is not inside a repository.
+    NotFound { cwd: PathBuf },
+    /// The name given by `--name` is not in the name map.
+    UnknownName(UnknownRepoName),
+    /// The directory given by `--repo-path` is not a repository.
+    NotARepository(NotARepository),
+    /// The RID file could not be read.
+    Io { path: PathBuf, err: io::Error },
+    /// The RID file does not contain a valid RID.
+    Invalid {
+        path: PathBuf,
+        err: <RepoId as FromStr>::Err,
+    },
+}
+
+impl fmt::Display for RidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound { cwd } => write!(
+                f,
+                "no `--rid`, `--name` or `--repo-path` was given and `{}` is not \
+                 inside a Radicle repository",
+                cwd.display()
+            ),
+            Self::UnknownName(err) => write!(f, "{err}"),
+            Self::NotARepository(err) => write!(f, "{err}"),
+            Self::Io { path, err } => write!(f, "failed to read `{}`: {err}", path.display()),
+            Self::Invalid { path, err } => {
+                write!(f, "invalid RID in `{}`: {err}", path.display())
+            }
+        }
+    }
+}
+
+impl error::Error for RidError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::NotFound { .. } => None,
+            Self::UnknownName(err) => Some(err),
+            Self::NotARepository(err) => Some(err),
+            Self::Io { err, .. } => Some(err),
+            Self::Invalid { err, .. } => Some(err),
+        }
+    }
+}
+
+impl From<UnknownRepoName> for RidError {
+    fn from(err: UnknownRepoName) -> Self {
+        Self::UnknownName(err)
+    }
+}
+
+impl From<NotARepository> for RidError {
+    fn from(err: NotARepository) -> Self {
+        Self::NotARepository(err)
+    }
+}
+
+/// The repository name given by `--name` is not in the name map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRepoName {
+    /// The name that was looked up.
+    pub name: String,
+    /// The names that are known, in sorted order.
+    pub available: Vec<String>,
+}
+
+impl fmt::Display for UnknownRepoName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown repository name `{}`", self.name)?;
+        if self.available.is_empty() {
+            write!(f, ", no repository names are configured")
+        } else {
+            write!(f, ", available names: {}", self.available.join(", "))
+        }
+    }
+}
+
+impl error::Error for UnknownRepoName {}
+
+/// Sync repositories to and from the network
+#[derive(Debug, Clone, PartialEq, Eq, Parser)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[command(override_usage(SYNC_USAGE))]
+#[command(after_help = SYNC_HELP)]
+#[clap(help_template = HELP_TEMPLATE)]
+pub struct Sync {
+    /// Repository Identifier to be synchronized. May be given more than once
+    /// to sync several repositories
+    #[arg(
+        id = "rid",
+        long = "rid",
+        global = true,
+        value_name = "rid",
+        action = clap::ArgAction::Append
+    )]
+    pub rids: Vec<RepoId>,
+    /// Name of the repository to be synchronized, as given in the local name
+    /// map
+    #[arg(long, global = true, value_name = "name", conflicts_with = "rid")]
+    pub name: Option<String>,
+    /// Path to the working directory of the repository to be synchronized
+    #[arg(
+        long,
+        global = true,
+        value_name = "dir",
+        conflicts_with_all = ["rid", "name"]
+    )]
+    pub repo_path: Option<PathBuf>,
+    /// Output debug information, if any
+    #[arg(long, global = true)]
+    pub debug: bool,
+    /// Out verbose information, if any
+    #[arg(long, short, global = true)]
+    pub verbose: bool,
+    /// Suppress all output other than errors
+    #[arg(long, short, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    #[command(flatten)]
+    pub sync: SyncModeArgs,
+    #[command(flatten)]
+    pub settings: SyncSettingsArgs,
+    #[command(subcommand)]
+    pub op: Option<Operation>,
+}
+
+impl Sync {
+    /// Resolve the repositories to be synchronized, either from `--rid` or
+    /// by looking up `--name` in the given name map.
+    pub fn resolve_name(
+        &self,
+        names: &BTreeMap<String, RepoId>,
+    ) -> Result<Vec<RepoId>, UnknownRepoName> {
+        match (self.rids.as_slice(), &self.name) {
+            ([], Some(name)) => match names.get(name) {
+                Some(rid) => Ok(vec![rid.clone()]),
+                None => Err(UnknownRepoName {
+                    name: name.clone(),
+                    available: names.keys().cloned().collect(),
+                }),
+            },
+            (rids, _) => Ok(rids.to_vec()),
+        }
+    }
+
+    /// Resolve the repositories to be synchronized, either from `--rid` or
+    /// by resolving the working directory given by `--repo-path`.
+    pub fn resolve_repo_path(
+        &self,
+        resolver: &impl RepoResolver,
+    ) -> Result<Vec<RepoId>, NotARepository> {
+        match (self.rids.as_slice(), &self.repo_path) {
+            ([], Some(path)) => resolver.resolve(path).map(|rid| vec![rid]),
+            (rids, _) => Ok(rids.to_vec()),
+        }
+    }
+
+    /// Resolve the sync mode from the parsed arguments. The `inventory`
+    /// subcommand and the `--inventory` flag both resolve to
+    /// [`SyncMode::Inventory`].
+    pub fn mode(&self) -> SyncMode {
+        if let Some(Operation::Inventory) = self.op {
+            return SyncMode::Inventory;
+        }
+        let direction = SyncDirection::from(self.sync.directions.clone());
+        let settings = self.settings.clone().resolve(&direction);
+
+        SyncMode::new(self.sync.clone(), Some(settings), self.rids.clone())
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    Sync(Sync),
+}
+
+/// How much output to print, as given by `--quiet` and `--verbose`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    /// Print errors only.
+    Quiet,
+    /// Print the usual output.
+    #[default]
+    Normal,
+    /// Print additional details.
+    Verbose,
+}
+
+/// Options that parse, but do not describe a sync that can be carried out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionsError {
+    /// Both directions were turned off.
+    NothingToSync,
+    /// `status --all` was combined with a specific repository.
+    AllWithRepo,
+    /// A direction flag, e.g. `--fetch`, was combined with `--inventory`.
+    DirectionWithInventory { flag: &'static str },
+    /// A repository, e.g. `--rid`, was combined with `--inventory`.
+    RepoWithInventory { flag: &'static str },
+    /// `--expand-env` was given, but the options were parsed without
+    /// expanding placeholders, e.g. by [`Parser::parse`].
+    ExpandEnvUnsupported,
+    /// `--wait-ack` was given, but the sync only fetches.
+    WaitAckWithoutAnnounce,
+    /// `--network-size` was given, but the replica target is not `auto`.
+    NetworkSizeWithoutAuto,
+    /// The resolved settings are invalid.
+    Settings(SettingsError),
+}
+
+impl From<SettingsError> for OptionsError {
+    fn from(err: SettingsError) -> Self {
+        Self::Settings(err)
+    }
+}
+
+impl fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NothingToSync => write!(
+                f,
+                "`--no-fetch` and `--no-announce` together would neither fetch nor \
+                 announce, so there is nothing to do; drop one of them"
+            ),
+            Self::AllWithRepo => write!(
+                f,
+                "`status --all` cannot be used with `--rid`, `--name` or `--repo-path`"
+            ),
+            Self::DirectionWithInventory { flag } => write!(
+                f,
+                "`{flag}` cannot be used with `--inventory`: announcing the inventory \
+                 does not fetch or sync a repository; drop either `{flag}` or `--inventory`"
+            ),
+            Self::RepoWithInventory { flag } => write!(
+                f,
+                "`{flag}` cannot be used with `--inventory`: announcing the inventory \
+                 does not target a repository; drop either `{flag}` or `--inventory`"
+            ),
+            Self::ExpandEnvUnsupported => write!(
+                f,
+                "`--expand-env` is only supported when parsing with \
+                 `Options::parse_with_config`"
+            ),
+            Self::WaitAckWithoutAnnounce => write!(
+                f,
+                "`--wait-ack` waits for seeds to acknowledge an announcement, but only \
+                 fetching does not announce; drop `--wait-ack` or add `--announce`"
+            ),
+            Self::NetworkSizeWithoutAuto => write!(
+                f,
+                "`--network-size` is only used by `--replicas auto`; drop it or add \
+                 `--replicas auto`"
+            ),
+            Self::Settings(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl error::Error for OptionsError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::NothingToSync
+            | Self::AllWithRepo
+            | Self::DirectionWithInventory { .. }
+            | Self::RepoWithInventory { .. }
+            | Self::ExpandEnvUnsupported
+            | Self::WaitAckWithoutAnnounce
+            | Self::NetworkSizeWithoutAuto => None,
+            Self::Settings(err) => Some(err),
+        }
+    }
+}
+
+/// The options after resolving the sync mode, see [`Options::resolved`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ResolvedOptions {
+    /// The resolved sync mode, including the repositories given by `--rid`.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub mode: SyncMode,
+    /// The operation to perform instead of syncing, if any.
+    pub op: Option<Operation>,
+}
+
+/// Debug information printed by `--debug`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DebugReport {
+    /// The arguments as parsed.
+    pub args: Sync,
+    /// The sync mode resolved from the arguments.
+    pub mode: SyncMode,
+    /// Warnings about the resolved mode, as shown by `--dry-run --verbose`.
+    pub warnings: Vec<String>,
+}
+
+impl fmt::Display for DebugReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Parsed arguments:")?;
+        writeln!(f, "{:#?}", self.args)?;
+        writeln!(f, "Resolved mode:")?;
+        write!(f, "{:#?}", self.mode)?;
+        if !self.warnings.is_empty() {
+            write!(f, "\nWarnings:")?;
+            for warning in &self.warnings {
+                write!(f, "\n{warning}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Radicle command line interface
+#[derive(Debug, Parser)]
+#[clap(help_template = HELP_TEMPLATE)]
+#[command(version = "nix-0be7453")]
+#[command(propagate_version = true)]
 #[command(override_usage(r#"rad <command> [--help | -h]"#))]
 pub struct Options {
+    /// Print the resolved options as JSON instead of syncing
+    #[arg(long, global = true)]
+    json: bool,
+    /// Print what would be synced without syncing
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Read sync defaults from the given TOML file [default: $RAD_HOME/sync.toml]
+    #[arg(long, global = true, value_name = "path")]
+    config: Option<PathBuf>,
     #[command(subcommand)]
     cmds: Commands,
 }
+
+impl Options {
+    /// Parse the command line, taking defaults from the config file given by
+    /// `--config`, or from [`SyncConfig::default_path`] if it exists. Seeds
+    /// are parsed with [`Seed::parse_expanding_env`] if `--expand-env` is
+    /// given. Exits on error, like [`Parser::parse`].
+    ///
+    /// This is the only way to parse the options that supports
+    /// `--expand-env`; [`Options::validate`] rejects it otherwise.
+    pub fn parse_with_config() -> Self {
+        Self::try_parse_with_config_from(env::args_os()).unwrap_or_else(|err| err.exit())
+    }
+
+    /// Like [`Options::parse_with_config`], parsing the given arguments and
+    /// returning any error, like [`Parser::try_parse_from`].
+    pub fn try_parse_with_config_from<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let args = args.into_iter().map(Into::into).collect::<Vec<OsString>>();
+        // `--expand-env` changes how seeds are parsed, so it is looked for
+        // first, with seeds taken as they are.
+        let seeds_parsed_with = |parser: clap::builder::ValueParser| {
+            Self::command().mut_subcommand("sync", |sync| {
+                sync.mut_arg("seeds", |arg| arg.value_parser(parser.clone()))
+                    .mut_arg("fallback_seeds", |arg| arg.value_parser(parser))
+            })
+        };
+        let expand_env = seeds_parsed_with(clap::builder::ValueParser::string())
+            .ignore_errors(true)
+            .try_get_matches_from(&args)
+            .ok()
+            .and_then(|matches| {
+                matches
+                    .subcommand_matches("sync")?
+                    .try_get_one::<bool>("expand_env")
+                    .ok()?
+                    .copied()
+            })
+            .unwrap_or_default();
+        let cmd = if expand_env {
+            seeds_parsed_with(clap::builder::ValueParser::new(Seed::parse_expanding_env))
+        } else {
+            Self::command()
+        };
+        let mut opts = Self::from_arg_matches(&cmd.clone().try_get_matches_from(&args)?)?;
+        let path = match &opts.config {
+            Some(path) => Some(path.clone()),
+            // Without `serde`, a config file is only an error if asked for.
+            None => {
+                SyncConfig::default_path().filter(|path| cfg!(feature = "serde") && path.exists())
+            }
+        };
+        if let Some(path) = path {
+            let config = SyncConfig::load(&path)
+                .map_err(|err| Self::command().error(clap::error::ErrorKind::Io, err))?;
+            opts = Self::from_arg_matches(&config.apply(cmd).try_get_matches_from(args)?)?;
+        }
+        let Commands::Sync(sync) = &mut opts.cmds;
+        sync.settings.env_expanded = expand_env;
+
+        Ok(opts)
+    }
+
+    /// Check that the options describe a sync that can be carried out.
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        let Commands::Sync(sync) = &self.cmds;
+
+        if sync.settings.expand_env && !sync.settings.env_expanded {
+            return Err(OptionsError::ExpandEnvUnsupported);
+        }
+        // The network size is only used to derive an `auto` replica target.
+        if sync.settings.network_size.is_some() && sync.settings.replicas != Some(Replicas::Auto) {
+            return Err(OptionsError::NetworkSizeWithoutAuto);
+        }
+
+        if sync.mode() == SyncMode::Inventory {
+            let directions = &sync.sync.directions;
+            let direction = [
+                (directions.fetch, "--fetch"),
+                (directions.announce, "--announce"),
+                (directions.no_fetch, "--no-fetch"),
+                (directions.no_announce, "--no-announce"),
+            ];
+            if let Some((_, flag)) = direction.into_iter().find(|(given, _)| *given) {
+                return Err(OptionsError::DirectionWithInventory { flag });
+            }
+            let repo = [
+                (!sync.rids.is_empty(), "--rid"),
+                (sync.name.is_some(), "--name"),
+                (sync.repo_path.is_some(), "--repo-path"),
+            ];
+            if let Some((_, flag)) = repo.into_iter().find(|(given, _)| *given) {
+                return Err(OptionsError::RepoWithInventory { flag });
+            }
+        }
+        if sync.sync.directions.no_fetch && sync.sync.directions.no_announce {
+            return Err(OptionsError::NothingToSync);
+        }
+        // `--rid` given before `status` is not seen by the subcommand's own
+        // conflict checks.
+        if let Some(Operation::Status { all: true, .. }) = sync.op {
+            if !sync.rids.is_empty() || sync.name.is_some() || sync.repo_path.is_some() {
+                return Err(OptionsError::AllWithRepo);
+            }
+        }
+        match sync.mode() {
+            SyncMode::Repo {
+                settings,
+                direction,
+                ..
+            } => {
+                if sync.settings.wait_ack && direction == SyncDirection::Fetch {
+                    return Err(OptionsError::WaitAckWithoutAnnounce);
+                }
+                Ok(settings.validate()?)
+            }
+            SyncMode::Inventory => Ok(()),
+        }
+    }
+
+    /// Resolve the repositories to sync: those given by `--rid`, the one
+    /// `--name` maps to in `names`, or the one `resolver` finds at
+    /// `--repo-path`. If none of these were given, the RID is read from the
+    /// [`RID_FILE`] of the repository containing `cwd`.
+    ///
+    /// Returns no repositories if the sync mode does not operate on a
+    /// repository, e.g. with `--inventory`.
+    pub fn resolve_rid(
+        &self,
+        cwd: &Path,
+        names: &BTreeMap<String, RepoId>,
+        resolver: &impl RepoResolver,
+    ) -> Result<Vec<RepoId>, RidError> {
+        let Commands::Sync(sync) = &self.cmds;
+
+        if !sync.mode().requires_rid() {
+            return Ok(Vec::new());
+        }
+        if let Some(Operation::Status { all: true, .. }) = sync.op {
+            return Ok(Vec::new());
+        }
+        // `--rid`, `--name` and `--repo-path` conflict, so at most one of
+        // these finds anything.
+        let rids = sync.resolve_name(names)?;
+        if !rids.is_empty() {
+            return Ok(rids);
+        }
+        let rids = sync.resolve_repo_path(resolver)?;
+        if !rids.is_empty() {
+            return Ok(rids);
+        }
+        for dir in cwd.ancestors() {
+            let path = dir.join(RID_FILE);
+            match fs::read_to_string(&path) {
+                Ok(contents) => match contents.trim().parse() {
+                    Ok(rid) => return Ok(vec![rid]),
+                    Err(err) => return Err(RidError::Invalid { path, err }),
+                },
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(RidError::Io { path, err }),
+            }
+        }
+        Err(RidError::NotFound {
+            cwd: cwd.to_path_buf(),
+        })
+    }
+
+    /// The shell to write completions for, if `rad sync completions` was
+    /// invoked.
+    pub fn completions(&self) -> Option<Shell> {
+        match &self.cmds {
+            Commands::Sync(sync) => match sync.op {
+                Some(Operation::Completions { shell }) => Some(shell),
+                _ => None,
+            },
+        }
+    }
+
+    /// Whether `--json` was given.
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Whether `--dry-run` was given.
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Whether `--verbose` was given.
+    pub fn verbose(&self) -> bool {
+        match &self.cmds {
+            Commands::Sync(sync) => sync.verbose,
+        }
+    }
+
+    /// The verbosity resolved from `--quiet` and `--verbose`, which cannot
+    /// both be given.
+    pub fn verbosity(&self) -> Verbosity {
+        match &self.cmds {
+            Commands::Sync(sync) if sync.quiet => Verbosity::Quiet,
+            Commands::Sync(sync) if sync.verbose => Verbosity::Verbose,
+            Commands::Sync(_) => Verbosity::Normal,
+        }
+    }
+
+    /// The options after resolving the sync mode, as printed by `--json`.
+    pub fn resolved(&self) -> ResolvedOptions {
+        match &self.cmds {
+            Commands::Sync(sync) => ResolvedOptions {
+                mode: sync.mode(),
+                op: sync.op.clone(),
+            },
+        }
+    }
+
+    /// A key identifying what this invocation does, for caching its results.
+    ///
+    /// This is a hash of the resolved sync mode, including any `--rid`, in
+    /// the canonical form given by [`SyncMode::to_args`], the repository as
+    /// given by `--name` or `--repo-path`, `--include-known` and
+    /// `--known-filter`, which add seeds only once the node's peers are
+    /// known, and the subcommand with the options that change what it does.
+    /// Flags that only change how output is shown are left out: `--verbose`,
+    /// `--quiet`, `--debug`, `--json` and `--dry-run`, as well as the status
+    /// display flags, see [`Operation`]. So is `--config`, whose values are
+    /// part of the resolved mode.
+    pub fn args_fingerprint(&self) -> String {
+        let Commands::Sync(sync) = &self.cmds;
+        let mut key = sync.mode().to_args();
+
+        if sync.settings.include_known {
+            key.push("--include-known".to_owned());
+        }
+        if let Some(filter) = &sync.settings.known_filter {
+            key.extend(["--known-filter".to_owned(), filter.clone()]);
+        }
+        if let Some(name) = &sync.name {
+            key.extend(["--name".to_owned(), name.clone()]);
+        }
+        if let Some(path) = &sync.repo_path {
+            key.extend(["--repo-path".to_owned(), path.display().to_string()]);
+        }
+        if let Some(op) = &sync.op {
+            key.extend(op.key_args());
+        }
+        format!("{:016x}", fnv1a(key.join("\0").as_bytes()))
+    }
+
+    /// Whether `--debug` was given.
+    pub fn debug(&self) -> bool {
+        match &self.cmds {
+            Commands::Sync(sync) => sync.debug,
+        }
+    }
+
+    /// The debug information printed by `--debug`: the arguments as parsed,
+    /// the sync mode they resolve to, and any warnings about it.
+    pub fn debug_report(&self) -> DebugReport {
+        match &self.cmds {
+            Commands::Sync(sync) => {
+                let mode = sync.mode();
+                let warnings = match &mode {
+                    SyncMode::Repo { settings, .. } => settings.replica_warning(),
+                    SyncMode::Inventory => None,
+                };
+                DebugReport {
+                    args: sync.clone(),
+                    mode,
+                    warnings: warnings.into_iter().collect(),
+                }
+            }
+        }
+    }
+
+    /// The name of the invoked subcommand, resolved from the operation and
+    /// sync mode, e.g. `"status"`, `"sync"`, or `"inventory"`.
+    pub fn subcommand_name(&self) -> &'static str {
+        match &self.cmds {
+            Commands::Sync(sync) => match sync.op {
+                Some(Operation::Status { .. }) => "status",
+                Some(Operation::Inventory) => "inventory",
+                Some(Operation::Completions { .. }) => "completions",
+                None if sync.sync.inventory => "inventory",
+                None => "sync",
+            },
+        }
+    }
+}
+
+/// Write a completion script for the whole `rad` command tree.
+pub fn write_completions(shell: Shell, out: &mut impl io::Write) {
+    clap_complete::generate(shell, &mut Options::command(), "rad", out);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, sync::Mutex};
+
+    use super::*;
+
+    /// Held while reading or changing the environment, which clap reads the
+    /// `RAD_SYNC_*` variables from.
+    static ENV: Mutex<()> = Mutex::new(());
+
+    /// Run `f` with the given environment variables set, removing them after.
+    fn with_env<T>(vars: &[(&str, &str)], f: impl FnOnce() -> T) -> T {
+        let _env = ENV.lock().unwrap_or_else(|err| err.into_inner());
+        for (var, value) in vars {
+            env::set_var(var, value);
+        }
+        let result = f();
+        for (var, _) in vars {
+            env::remove_var(var);
+        }
+        result
+    }
+
+    fn try_parse(args: &[&str]) -> Result<Options, clap::Error> {
+        with_env(&[], || {
+            Options::try_parse_from(["rad", "sync"].iter().chain(args))
+        })
+    }
+
+    fn parse(args: &[&str]) -> Options {
+        try_parse(args).unwrap()
+    }
+
+    const NID: &str = "z6MksFqXN3Yhqk8pTJdUGLwATkRfQvwZXPqR2qMEhbS9wzpT";
+    const OTHER_NID: &str = "z6MkrLMMsiPWUcNPHcRajuMi9mDfYckSoJyPwwnknocNYPm7";
+
+    fn nid(s: &str) -> NodeId {
+        s.parse().unwrap()
+    }
+
+    fn sync(args: &[&str]) -> Sync {
+        let Commands::Sync(sync) = parse(args).cmds;
+        sync
+    }
+
+    fn settings(args: &[&str]) -> SyncSettings {
+        match sync(args).mode() {
+            SyncMode::Repo { settings, .. } => settings,
+            SyncMode::Inventory => panic!("expected a repository sync"),
+        }
+    }
+
+    const RID: &str = "rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5";
+
+    fn rid(s: &str) -> RepoId {
+        s.parse().unwrap()
+    }
+
+    /// Known peers given up front, in place of the node's.
+    struct MockPeers(Vec<KnownPeer>);
+
+    impl KnownPeers for MockPeers {
+        fn known_peers(&self) -> Vec<KnownPeer> {
+            self.0.clone()
+        }
+    }
+
+    fn known_peers() -> MockPeers {
+        MockPeers(vec![
+            KnownPeer {
+                nid: nid(NID),
+                alias: Some("seed.radicle.xyz".to_owned()),
+            },
+            KnownPeer {
+                nid: nid(OTHER_NID),
+                alias: Some("alice".to_owned()),
+            },
+        ])
+    }
+
+    /// A status row for testing sorting and rendering.
+    struct Row {
+        nid: NodeId,
+        alias: Option<&'static str>,
+        state: SyncState,
+    }
+
+    impl Peer for Row {
+        fn nid(&self) -> &NodeId {
+            &self.nid
+        }
+
+        fn alias(&self) -> Option<&str> {
+            self.alias
+        }
+    }
+
+    impl StatusRow for Row {
+        fn state(&self) -> SyncState {
+            self.state
+        }
+    }
+
+    fn row(id: &str, alias: &'static str, state: SyncState) -> Row {
+        Row {
+            nid: nid(id),
+            alias: Some(alias),
+            state,
+        }
+    }
+
+    /// Seeds with distinct, valid Node IDs.
+    fn seeds(n: usize) -> Vec<Seed> {
+        (0..n)
+            .map(|i| {
+                let id = format!("z6Mk{}", &BASE58_ALPHABET[i..i + 44]);
+                Seed::from(nid(&id))
+            })
+            .collect()
+    }
+
+    fn seed(s: &str) -> Seed {
+        s.parse().unwrap()
+    }
+
+    fn direction(args: &[&str]) -> SyncDirection {
+        match sync(args).mode() {
+            SyncMode::Repo { direction, .. } => direction,
+            SyncMode::Inventory => panic!("expected a repository sync"),
+        }
+    }
+
+    /// Resolves only the given directory, to the given repository.
+    struct MockResolver(PathBuf, RepoId);
+
+    impl RepoResolver for MockResolver {
+        fn resolve(&self, path: &Path) -> Result<RepoId, NotARepository> {
+            if path == self.0 {
+                Ok(self.1.clone())
+            } else {
+                Err(NotARepository {
+                    path: path.to_path_buf(),
+                })
+            }
+        }
+    }
+
+    fn resolver() -> MockResolver {
+        MockResolver(PathBuf::from("/src/heartwood"), rid(RID))
+    }
+
+    fn validate(args: &[&str]) -> Result<(), OptionsError> {
+        parse(args).validate()
+    }
+
+    const OTHER_RID: &str = "rad:z4V1sjrXqjvFdnCUbxPFqd5p4DtH5";
+
+    /// A directory under the system's temporary directory, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = env::temp_dir().join(format!("rad-sync-{}-{name}", std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn resolve_rid(args: &[&str], cwd: &Path) -> Result<Vec<RepoId>, RidError> {
+        let names = BTreeMap::from([("heartwood".to_owned(), rid(OTHER_RID))]);
+        parse(args).resolve_rid(cwd, &names, &resolver())
+    }
+
+    fn settings_with_env(vars: &[(&str, &str)], args: &[&str]) -> SyncSettings {
+        let opts = with_env(vars, || {
+            Options::try_parse_from(["rad", "sync"].iter().chain(args))
+        });
+        match opts.unwrap().resolved().mode {
+            SyncMode::Repo { settings, .. } => settings,
+            SyncMode::Inventory => panic!("expected a repository sync"),
+        }
+    }
+
+    const THIRD_NID: &str = "z6MkireRatUThvd3qzfKht1S44wpm4FEWSSa4PRMTSQZ3voM";
+
+    /// Parse `args`, convert the mode back into arguments, and parse those.
+    fn round_trip(args: &[&str]) -> (SyncMode, SyncMode) {
+        let mode = sync(args).mode();
+        let args = mode.to_args();
+        let reparsed = sync(&args.iter().map(String::as_str).collect::<Vec<_>>()).mode();
+
+        (mode, reparsed)
+    }
+
+    /// Write `contents` to a config file and load it.
+    #[cfg(feature = "serde")]
+    fn load_config(name: &str, contents: &str) -> Result<SyncConfig, ConfigError> {
+        let dir = TempDir::new(name);
+        let path = dir.0.join(CONFIG_FILE);
+        fs::write(&path, contents).unwrap();
+
+        SyncConfig::load(&path)
+    }
+
+    #[cfg(feature = "serde")]
+    fn settings_with_config(
+        config: SyncConfig,
+        vars: &[(&str, &str)],
+        args: &[&str],
+    ) -> SyncSettings {
+        let matches = with_env(vars, || {
+            config
+                .apply(Options::command())
+                .try_get_matches_from(["rad", "sync"].iter().chain(args))
+        });
+        let opts = Options::from_arg_matches(&matches.unwrap()).unwrap();
+        match opts.resolved().mode {
+            SyncMode::Repo { settings, .. } => settings,
+            SyncMode::Inventory => panic!("expected a repository sync"),
+        }
+    }
+
+    fn fingerprint(args: &[&str]) -> String {
+        parse(args).args_fingerprint()
+    }
+
+    fn peer(id: &str, alias: Option<&str>) -> KnownPeer {
+        KnownPeer {
+            nid: nid(id),
+            alias: alias.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn lookup_by_str() {
+        let nodes = std::collections::HashMap::from([(nid(NID), 1)]);
+        assert_eq!(nodes.get(NID), Some(&1));
+        assert_eq!(nodes.get(OTHER_NID), None);
+
+        let repos = std::collections::HashMap::from([(rid(RID), 1)]);
+        assert_eq!(repos.get(RID), Some(&1));
+    }
+
+    #[test]
+    fn parse_node_id() {
+        assert_eq!(nid(NID).to_string(), NID);
+        assert_eq!("".parse::<NodeId>(), Err(ParseNodeIdError::InvalidPrefix));
+        assert_eq!(
+            "z6Mks".parse::<NodeId>(),
+            Err(ParseNodeIdError::InvalidLength(5))
+        );
+        // `0` is not in the base58 alphabet.
+        assert_eq!(
+            NID.replace('X', "0").parse::<NodeId>(),
+            Err(ParseNodeIdError::InvalidCharacter('0'))
+        );
+    }
+
+    #[test]
+    fn invalid_seed_is_usage_error() {
+        let err = try_parse(&["--seed", "z6Mks"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn parse_repo_id() {
+        assert_eq!(rid(RID).as_str(), RID);
+        assert_eq!(rid("z3gqcJUoA1n9HaHKufZs5FCSGazv5"), rid(RID));
+        assert_eq!(rid(RID).to_string(), RID);
+        assert_eq!(rid(RID).to_bytes().len(), 20);
+    }
+
+    #[test]
+    fn parse_invalid_repo_id() {
+        assert_eq!("".parse::<RepoId>(), Err(ParseRepoIdError::Empty));
+        assert_eq!("rad:".parse::<RepoId>(), Err(ParseRepoIdError::Empty));
+        assert_eq!(
+            "git:z3gqcJUoA1n9HaHKufZs5FCSGazv5".parse::<RepoId>(),
+            Err(ParseRepoIdError::InvalidPrefix)
+        );
+        assert_eq!(
+            "rad:z3gqcJUoA1n9HaHKufZs5FCSGazv0".parse::<RepoId>(),
+            Err(ParseRepoIdError::InvalidCharacter('0'))
+        );
+
+        let err = try_parse(&["--rid", "heartwood"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn node_id_order() {
+        assert!(nid(THIRD_NID) < nid(OTHER_NID));
+        assert!(nid(OTHER_NID) < nid(NID));
+    }
+
+    #[test]
+    fn include_known_peers() {
+        let mut args = sync(&["--include-known"]).settings;
+        args.include_known(&known_peers());
+
+        assert_eq!(
+            args.seeds,
+            [Seed::from(nid(NID)), Seed::from(nid(OTHER_NID))]
+        );
+    }
+
+    #[test]
+    fn include_known_peers_with_filter() {
+        let mut args = sync(&["--include-known", "--known-filter", "radicle"]).settings;
+        args.include_known(&known_peers());
+
+        assert_eq!(args.seeds, [Seed::from(nid(NID))]);
+    }
+
+    #[test]
+    fn known_peers_only_included_with_flag() {
+        let mut args = sync(&[]).settings;
+        args.include_known(&known_peers());
+
+        assert!(args.seeds.is_empty());
+        assert!(try_parse(&["--known-filter", "radicle"]).is_err());
+    }
+
+    #[test]
+    fn shuffle_is_deterministic() {
+        let shuffled = |shuffle_seed| {
+            SyncSettings {
+                seeds: seeds(8),
+                shuffle_seed,
+                ..SyncSettings::default()
+            }
+            .effective_seeds()
+        };
+
+        assert_eq!(shuffled(None), seeds(8));
+        assert_eq!(shuffled(Some(1)), shuffled(Some(1)));
+        assert_ne!(shuffled(Some(1)), shuffled(Some(2)));
+        assert_ne!(shuffled(Some(1)), seeds(8));
+    }
+
+    #[test]
+    fn shuffle_seed_flag() {
+        assert_eq!(settings(&["--shuffle-seed", "42"]).shuffle_seed, Some(42));
+        assert_eq!(settings(&[]).shuffle_seed, None);
+    }
+
+    #[test]
+    fn parse_seed() {
+        assert_eq!(seed(NID), Seed::from(nid(NID)));
+        assert_eq!(
+            seed(&format!("{NID}@seed.radicle.xyz:8776")).addr,
+            Some(Address {
+                host: Host::Dns("seed.radicle.xyz".to_owned()),
+                port: 8776
+            })
+        );
+        assert_eq!(
+            seed(&format!("{NID}@127.0.0.1:8776")).addr,
+            Some(Address {
+                host: Host::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+                port: 8776
+            })
+        );
+        assert_eq!(
+            seed(&format!("{NID}@[::1]:8776")).addr,
+            Some(Address {
+                host: Host::Ip(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+                port: 8776
+            })
+        );
+    }
+
+    #[test]
+    fn parse_seed_missing_port() {
+        assert_eq!(
+            format!("{NID}@seed.radicle.xyz").parse::<Seed>(),
+            Err(ParseSeedError::MissingPort)
+        );
+        assert_eq!(
+            format!("{NID}@[::1]").parse::<Seed>(),
+            Err(ParseSeedError::MissingPort)
+        );
+    }
+
+    #[test]
+    fn seed_display_round_trip() {
+        for s in [
+            NID.to_owned(),
+            format!("{NID}@seed.radicle.xyz:8776"),
+            format!("{NID}@127.0.0.1:8776"),
+            format!("{NID}@[::1]:8776"),
+        ] {
+            assert_eq!(seed(&s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn dedup_seeds_on_parse() {
+        let with_addr = format!("{NID}@seed.radicle.xyz:8776");
+        let settings = settings(&["--seed", NID, "--seed", OTHER_NID, "--seed", &with_addr]);
+
+        assert_eq!(settings.seeds, [seed(&with_addr), seed(OTHER_NID)]);
+    }
+
+    #[test]
+    fn dedup_fallback_seeds_against_primaries() {
+        let settings = settings(&[
+            "--seed",
+            NID,
+            "--fallback-seed",
+            &format!("{NID},{OTHER_NID}"),
+        ]);
+
+        assert_eq!(settings.seeds, [seed(NID)]);
+        assert_eq!(settings.fallback_seeds, [seed(OTHER_NID)]);
+    }
+
+    #[test]
+    fn seed_error_span() {
+        let input = format!("{NID}@host:notaport");
+        let err = input.parse::<Seed>().unwrap_err();
+        let (start, end) = err.span().unwrap();
+
+        assert_eq!(&input[start..end], "notaport");
+
+        let input = format!("{NID}@ho_st:8776");
+        let (start, end) = input.parse::<Seed>().unwrap_err().span().unwrap();
+        assert_eq!(&input[start..end], "ho_st");
+
+        let input = "z6Mkbad@host:8776";
+        let err = input.parse::<Seed>().unwrap_err();
+        assert_eq!(err.span(), Some((0, 7)));
+        assert_eq!(err.to_string(), "invalid Node ID length 7, expected 48");
+        assert_eq!("z6Mkbad".parse::<Seed>().unwrap_err().span(), Some((0, 7)));
+    }
+
+    #[test]
+    fn address_error_span() {
+        let err = "host:notaport".parse::<Address>().unwrap_err();
+        assert_eq!(err.span(), Some((5, 13)));
+        assert_eq!(ParseSeedError::MissingPort.span(), None);
+    }
+
+    #[test]
+    fn expand_env_in_seed() {
+        let expanded = with_env(&[("RAD_SYNC_TEST_HOST", "seed.radicle.xyz")], || {
+            Seed::parse_expanding_env(&format!("{NID}@${{RAD_SYNC_TEST_HOST}}:8776"))
+        });
+
+        assert_eq!(expanded, Ok(seed(&format!("{NID}@seed.radicle.xyz:8776"))));
+    }
+
+    #[test]
+    fn expand_env_missing_variable() {
+        let err = with_env(&[], || {
+            Seed::parse_expanding_env(&format!("{NID}@${{RAD_SYNC_TEST_UNSET}}:8776"))
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ParseSeedError::Env(ExpandEnvError::Unset {
+                var: "RAD_SYNC_TEST_UNSET".to_owned()
+            })
+        );
+        assert_eq!(
+            err.to_string(),
+            "environment variable `RAD_SYNC_TEST_UNSET` is not set"
+        );
+        assert_eq!(expand_env("${HOST"), Err(ExpandEnvError::Unclosed));
+    }
+
+    #[test]
+    fn seeds_not_expanded_by_default() {
+        assert!(format!("{NID}@${{HOST}}:8776").parse::<Seed>().is_err());
+    }
+
+    #[test]
+    fn expand_env_on_command_line() {
+        let home = TempDir::new("expand-env");
+        let seed_arg = format!("{NID}@${{RAD_SYNC_TEST_HOST}}:8776");
+        let opts = with_env(
+            &[
+                (ENV_RAD_HOME, &*home.0.display().to_string()),
+                ("RAD_SYNC_TEST_HOST", "seed.radicle.xyz"),
+            ],
+            || {
+                Options::try_parse_with_config_from([
+                    "rad",
+                    "sync",
+                    "--expand-env",
+                    "--seed",
+                    &seed_arg,
+                ])
+            },
+        )
+        .unwrap();
+
+        assert_eq!(opts.validate(), Ok(()));
+        match opts.resolved().mode {
+            SyncMode::Repo { settings, .. } => assert_eq!(
+                settings.seeds,
+                [seed(&format!("{NID}@seed.radicle.xyz:8776"))]
+            ),
+            SyncMode::Inventory => panic!("expected a repository sync"),
+        }
+
+        // Other ways of parsing leave placeholders alone, so the flag is
+        // rejected rather than ignored.
+        assert!(try_parse(&["--expand-env", "--seed", &seed_arg]).is_err());
+        assert_eq!(
+            parse(&["--expand-env", "--seed", NID]).validate(),
+            Err(OptionsError::ExpandEnvUnsupported)
+        );
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("2m"), parse_duration("120s"));
+        assert_eq!(parse_duration("2m"), Ok(time::Duration::from_secs(120)));
+        assert_eq!(parse_duration("30"), Ok(time::Duration::from_secs(30)));
+        assert_eq!(parse_duration("2m30s"), Ok(time::Duration::from_secs(150)));
+        assert_eq!(
+            parse_duration("500ms"),
+            Ok(time::Duration::from_millis(500))
+        );
+        assert_eq!(parse_duration("1h"), Ok(time::Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn parse_invalid_durations() {
+        assert!(parse_duration("2x").is_err());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("s").is_err());
+
+        let err = try_parse(&["--timeout", "2x"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        assert!(err.to_string().contains("`2x`"));
+    }
+
+    #[test]
+    fn format_durations() {
+        assert_eq!(format_duration(DEFAULT_SYNC_TIMEOUT), "9s");
+        assert_eq!(format_duration(time::Duration::from_secs(150)), "2m30s");
+        assert_eq!(format_duration(time::Duration::from_millis(500)), "500ms");
+        assert_eq!(format_duration(time::Duration::ZERO), "0s");
+    }
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("512B"), Ok(512));
+        assert_eq!(parse_size("500kB"), Ok(500_000));
+        assert_eq!(parse_size("100MB"), Ok(100_000_000));
+        assert_eq!(parse_size("1GiB"), Ok(1 << 30));
+        assert_eq!(parse_size("2 MiB"), Ok(2 << 20));
+        assert!(parse_size("1XB").is_err());
+        assert!(parse_size("").is_err());
+    }
+
+    #[test]
+    fn duration_error_span() {
+        let input = "1m30x";
+        let (start, end) = parse_duration(input).unwrap_err().span().unwrap();
+
+        assert_eq!(&input[start..end], "30x");
+    }
+
+    #[test]
+    fn replicas_satisfied_by_explicit_seeds() {
+        let settings = SyncSettings::builder()
+            .replicas(2)
+            .seeds([nid(NID), nid(OTHER_NID)])
+            .build()
+            .unwrap();
+        let replicated = [
+            (nid(NID), SeedOutcome::Replicated),
+            (nid(OTHER_NID), SeedOutcome::Replicated),
+        ];
+
+        assert!(settings.replicas_satisfied(&replicated));
+    }
+
+    #[test]
+    fn replicas_not_satisfied_by_too_few_seeds() {
+        let settings = SyncSettings::builder()
+            .replicas(2)
+            .seeds([nid(NID), nid(OTHER_NID)])
+            .build()
+            .unwrap();
+        // The same node only counts once.
+        let replicated = [
+            (nid(NID), SeedOutcome::Replicated),
+            (nid(NID), SeedOutcome::Replicated),
+        ];
+
+        assert!(!settings.replicas_satisfied(&replicated));
+        assert!(!settings.replicas_satisfied(&[]));
+    }
+
+    #[test]
+    fn default_timeout_depends_on_direction() {
+        assert_eq!(settings(&[]).timeout, Some(DEFAULT_SYNC_TIMEOUT));
+        assert_eq!(settings(&["--fetch"]).timeout, Some(DEFAULT_SYNC_TIMEOUT));
+        assert_eq!(
+            settings(&["--announce"]).timeout,
+            Some(DEFAULT_ANNOUNCE_TIMEOUT)
+        );
+    }
+
+    #[test]
+    fn explicit_timeout_overrides_direction_default() {
+        assert_eq!(
+            settings(&["--announce", "--timeout", "20s"]).timeout,
+            Some(time::Duration::from_secs(20))
+        );
+    }
+
+    #[test]
+    fn validate_settings() {
+        assert_eq!(SyncSettings::default().validate(), Ok(()));
+
+        let settings = SyncSettings {
+            replicas: ReplicaTarget::Exact(0),
+            ..SyncSettings::default()
+        };
+        assert_eq!(settings.validate(), Err(SettingsError::ZeroReplicas));
+
+        let settings = SyncSettings {
+            timeout: Some(time::Duration::ZERO),
+            ..SyncSettings::default()
+        };
+        assert_eq!(settings.validate(), Err(SettingsError::ZeroTimeout));
+
+        let timeout = MAX_SYNC_TIMEOUT + time::Duration::from_secs(1);
+        let settings = SyncSettings {
+            timeout: Some(timeout),
+            ..SyncSettings::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            Err(SettingsError::TimeoutTooLong { timeout })
+        );
+    }
+
+    #[test]
+    fn validate_seeds_against_replicas() {
+        let settings = SyncSettings::builder()
+            .replicas(3)
+            .seed(nid(NID))
+            .build()
+            .unwrap();
+        assert_eq!(
+            settings.validate(),
+            Err(SettingsError::ReplicasExceedSeeds {
+                replicas: 3,
+                seeds: 1
+            })
+        );
+
+        let settings = SyncSettings::builder()
+            .replicas(1)
+            .seed(nid(NID))
+            .build()
+            .unwrap();
+        assert_eq!(settings.validate(), Ok(()));
+
+        // Only a target that was asked for has to be met.
+        let settings = SyncSettings::builder().seed(nid(NID)).build().unwrap();
+        assert_eq!(settings.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_seed_addresses() {
+        let with_addr = |addr| seed(&format!("{NID}@{addr}"));
+        let settings = SyncSettings::builder()
+            .seed(with_addr("seed.radicle.xyz:8776"))
+            .seed(with_addr("seed.radicle.garden:8776"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            settings.validate(),
+            Err(SettingsError::ConflictingSeedAddress { nid: nid(NID) })
+        );
+
+        let settings = SyncSettings {
+            seeds: vec![with_addr("seed.radicle.xyz:8776")],
+            fallback_seeds: vec![with_addr("seed.radicle.garden:8776")],
+            ..SyncSettings::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            Err(SettingsError::ConflictingSeedAddress { nid: nid(NID) })
+        );
+
+        // A seed without an address agrees with any address.
+        let settings = SyncSettings::builder()
+            .seed(nid(NID))
+            .seed(with_addr("seed.radicle.xyz:8776"))
+            .build()
+            .unwrap();
+        assert_eq!(settings.validate(), Ok(()));
+    }
+
+    #[test]
+    fn defaults_from_env_unset() {
+        assert_eq!(
+            with_env(&[], SyncSettings::with_defaults_from_env),
+            Ok(SyncSettings::default())
+        );
+    }
+
+    #[test]
+    fn defaults_from_env_set() {
+        let settings = with_env(
+            &[
+                (ENV_SYNC_TIMEOUT, "30s"),
+                (ENV_SYNC_REPLICAS, "2"),
+                (ENV_SYNC_SEEDS, &*format!("{NID},{OTHER_NID}")),
+            ],
+            SyncSettings::with_defaults_from_env,
+        );
+
+        assert_eq!(
+            settings,
+            Ok(SyncSettings {
+                replicas: ReplicaTarget::Exact(2),
+                explicit_replicas: true,
+                seeds: vec![Seed::from(nid(NID)), Seed::from(nid(OTHER_NID))],
+                timeout: Some(time::Duration::from_secs(30)),
+                ..SyncSettings::default()
+            })
+        );
+    }
+
+    #[test]
+    fn defaults_from_env_invalid() {
+        let err = with_env(
+            &[(ENV_SYNC_REPLICAS, "many")],
+            SyncSettings::with_defaults_from_env,
+        )
+        .unwrap_err();
+
+        assert_eq!(err, EnvError::new(ENV_SYNC_REPLICAS, "many".to_owned()));
+        assert_eq!(
+            err.to_string(),
+            "invalid value `many` for `RAD_SYNC_REPLICAS`"
+        );
+    }
+
+    #[test]
+    fn replicas_for_network_sizes() {
+        assert_eq!(replicas_for_network_size(0), 1);
+        assert_eq!(replicas_for_network_size(1), 1);
+        assert_eq!(replicas_for_network_size(10), 4);
+        assert_eq!(replicas_for_network_size(100), 10);
+        assert_eq!(replicas_for_network_size(1000), 32);
+    }
+
+    #[test]
+    fn replicas_auto() {
+        assert_eq!(
+            settings(&["--replicas", "auto", "--network-size", "100"]).replicas,
+            ReplicaTarget::Exact(10)
+        );
+        assert_eq!(
+            settings(&["--replicas", "auto"]).replicas,
+            ReplicaTarget::Exact(DEFAULT_REPLICAS)
+        );
+    }
+
+    #[test]
+    fn network_size_needs_replicas_auto() {
+        assert_eq!(
+            validate(&["--network-size", "100"]),
+            Err(OptionsError::NetworkSizeWithoutAuto)
+        );
+        assert_eq!(
+            validate(&["--replicas", "5", "--network-size", "100"]),
+            Err(OptionsError::NetworkSizeWithoutAuto)
+        );
+        assert_eq!(
+            validate(&["--replicas", "auto", "--network-size", "100"]),
+            Ok(())
+        );
+
+        let opts = with_env(&[(ENV_SYNC_REPLICAS, "auto")], || {
+            Options::try_parse_from(["rad", "sync", "--network-size", "100"])
+        });
+        assert_eq!(opts.unwrap().validate(), Ok(()));
+    }
+
+    #[test]
+    fn cap_oversubscribed_replicas() {
+        let cap = settings(&["--replicas", "10"]).cap_replicas(4);
+
+        assert_eq!(
+            cap,
+            ReplicaCap {
+                target: 10,
+                effective: 4
+            }
+        );
+        assert!(cap.is_oversubscribed());
+        assert_eq!(
+            cap.warning().unwrap(),
+            "replica target of 10 cannot be met, only 4 seed(s) are available"
+        );
+    }
+
+    #[test]
+    fn cap_satisfiable_replicas() {
+        let cap = settings(&["--replicas", "3"]).cap_replicas(4);
+        assert_eq!(cap.effective, 3);
+        assert!(!cap.is_oversubscribed());
+        assert_eq!(cap.warning(), None);
+
+        let cap = settings(&["--replicas", "max"]).cap_replicas(4);
+        assert_eq!(cap.effective, 4);
+        assert!(!cap.is_oversubscribed());
+    }
+
+    #[test]
+    fn same_seeds_in_any_order() {
+        let a = settings(&["--seed", NID, "--seed", OTHER_NID]);
+        let b = settings(&["--seed", OTHER_NID, "--seed", NID]);
+        let c = settings(&["--seed", NID]);
+
+        assert!(a.same_seeds_as(&b));
+        assert!(!a.same_seeds_as(&c));
+    }
+
+    #[test]
+    fn replicas_must_be_at_least_one() {
+        let err = validate(&["--replicas", "0"]).unwrap_err();
+        assert_eq!(err, OptionsError::Settings(SettingsError::ZeroReplicas));
+        assert_eq!(err.to_string(), "replica count must be at least 1");
+
+        assert_eq!(validate(&["--replicas", "1"]), Ok(()));
+        assert_eq!(validate(&["--replicas", "3"]), Ok(()));
+    }
+
+    #[test]
+    fn explicit_replicas_must_be_met_by_seeds() {
+        assert_eq!(
+            validate(&["--replicas", "3", "--seed", NID]),
+            Err(OptionsError::Settings(SettingsError::ReplicasExceedSeeds {
+                replicas: 3,
+                seeds: 1
+            }))
+        );
+        assert_eq!(validate(&["--replicas", "1", "--seed", NID]), Ok(()));
+        assert_eq!(
+            validate(&[
+                "--replicas",
+                "2",
+                "--seed",
+                NID,
+                "--fallback-seed",
+                OTHER_NID
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn seed_addresses_must_agree() {
+        let primary = format!("{NID}@seed.radicle.xyz:8776");
+        let fallback = format!("{NID}@127.0.0.1:8776");
+
+        assert_eq!(
+            validate(&["--seed", &primary, "--fallback-seed", &fallback]),
+            Err(OptionsError::Settings(
+                SettingsError::ConflictingSeedAddress { nid: nid(NID) }
+            ))
+        );
+        assert_eq!(validate(&["--seed", &primary, "--seed", NID]), Ok(()));
+    }
+
+    #[test]
+    fn default_replicas_need_not_be_met_by_seeds() {
+        assert_eq!(validate(&["--seed", NID]), Ok(()));
+
+        let opts = with_env(&[(ENV_SYNC_SEEDS, NID)], || {
+            Options::try_parse_from(["rad", "sync"])
+        });
+        assert_eq!(opts.unwrap().validate(), Ok(()));
+    }
+
+    #[test]
+    fn builder_defaults() {
+        assert_eq!(SyncSettings::builder().build(), Ok(SyncSettings::default()));
+    }
+
+    #[test]
+    fn builder_dedupes_seeds() {
+        let settings = SyncSettings::builder()
+            .seed(nid(NID))
+            .seeds([
+                seed(&format!("{NID}@seed.radicle.xyz:8776")),
+                seed(OTHER_NID),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            settings.seeds,
+            [
+                seed(&format!("{NID}@seed.radicle.xyz:8776")),
+                seed(OTHER_NID)
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_rejects_zero_timeout() {
+        assert_eq!(
+            SyncSettings::builder()
+                .timeout(time::Duration::ZERO)
+                .build(),
+            Err(SettingsError::ZeroTimeout)
+        );
+        assert_eq!(
+            SyncSettings::builder()
+                .no_timeout()
+                .build()
+                .unwrap()
+                .timeout,
+            None
+        );
+    }
+
+    #[test]
+    fn failed_seeds_do_not_count() {
+        let settings = SyncSettings::builder()
+            .replicas(2)
+            .seeds([nid(NID), nid(OTHER_NID)])
+            .build()
+            .unwrap();
+        let contacted = [
+            (nid(NID), SeedOutcome::Replicated),
+            (nid(OTHER_NID), SeedOutcome::Failed),
+        ];
+
+        assert!(!settings.replicas_satisfied(&contacted));
+    }
+
+    #[test]
+    fn no_timeout() {
+        assert_eq!(settings(&["--no-timeout"]).timeout, None);
+        assert_eq!(
+            with_env(&[(ENV_SYNC_TIMEOUT, "30s")], || {
+                Options::try_parse_from(["rad", "sync", "--no-timeout"])
+            })
+            .unwrap()
+            .resolved()
+            .mode,
+            sync(&["--no-timeout"]).mode()
+        );
+    }
+
+    #[test]
+    fn no_timeout_conflicts_with_timeout() {
+        let err = try_parse(&["--no-timeout", "--timeout", "30s"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn env_over_default() {
+        let env = [
+            (ENV_SYNC_REPLICAS, "2"),
+            (ENV_SYNC_TIMEOUT, "30s"),
+            (ENV_SYNC_SEEDS, NID),
+        ];
+        let settings = settings_with_env(&env, &[]);
+
+        assert_eq!(settings.replicas, ReplicaTarget::Exact(2));
+        assert_eq!(settings.timeout, Some(time::Duration::from_secs(30)));
+        assert_eq!(settings.seeds, [seed(NID)]);
+    }
+
+    #[test]
+    fn flag_over_env() {
+        let env = [
+            (ENV_SYNC_REPLICAS, "2"),
+            (ENV_SYNC_TIMEOUT, "30s"),
+            (ENV_SYNC_SEEDS, NID),
+        ];
+        let settings = settings_with_env(
+            &env,
+            &["--replicas", "1", "--timeout", "1m", "--seed", OTHER_NID],
+        );
+
+        assert_eq!(settings.replicas, ReplicaTarget::Exact(1));
+        assert_eq!(settings.timeout, Some(time::Duration::from_secs(60)));
+        assert_eq!(settings.seeds, [seed(OTHER_NID)]);
+    }
+
+    #[test]
+    fn invalid_env_is_usage_error() {
+        let err = with_env(&[(ENV_SYNC_REPLICAS, "many")], || {
+            Options::try_parse_from(["rad", "sync"])
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn env_defaults_match_flags() {
+        let env = [
+            (ENV_SYNC_REPLICAS, "auto"),
+            (ENV_SYNC_SEEDS, &*format!("{NID},{OTHER_NID},{NID}")),
+        ];
+
+        assert_eq!(
+            with_env(&env, SyncSettings::with_defaults_from_env),
+            Ok(settings_with_env(&env, &[]))
+        );
+    }
+
+    #[test]
+    fn env_timeout_is_bounded() {
+        let timeout = |value| {
+            with_env(
+                &[(ENV_SYNC_TIMEOUT, value)],
+                SyncSettings::with_defaults_from_env,
+            )
+        };
+
+        assert!(timeout("5h").is_err());
+        assert!(timeout("0s").is_err());
+        assert_eq!(timeout("1h").unwrap().timeout, Some(MAX_SYNC_TIMEOUT));
+    }
+
+    #[test]
+    fn max_bytes() {
+        assert_eq!(settings(&[]).max_bytes(), None);
+        assert_eq!(
+            settings(&["--max-bytes", "1GiB"]).max_bytes(),
+            Some(1 << 30)
+        );
+    }
+
+    #[test]
+    fn fallbacks_unused_when_primaries_succeed() {
+        let settings = settings(&[
+            "--replicas",
+            "1",
+            "--seed",
+            NID,
+            "--fallback-seed",
+            OTHER_NID,
+        ]);
+        let contacted = [(nid(NID), SeedOutcome::Replicated)];
+
+        assert!(settings.promote_fallbacks(&contacted).is_empty());
+    }
+
+    #[test]
+    fn fallbacks_promoted_when_primaries_fail() {
+        let settings = settings(&[
+            "--replicas",
+            "2",
+            "--seed",
+            NID,
+            "--fallback-seed",
+            &format!("{OTHER_NID},{THIRD_NID}"),
+        ]);
+        let contacted = [
+            (nid(NID), SeedOutcome::Failed),
+            (nid(THIRD_NID), SeedOutcome::Failed),
+        ];
+
+        // Fallbacks that were already contacted are not dialed again.
+        assert_eq!(settings.promote_fallbacks(&contacted), [seed(OTHER_NID)]);
+    }
+
+    #[test]
+    fn to_args_round_trip() {
+        let addr = format!("{NID}@[::1]:8776");
+        for args in [
+            &[][..],
+            &["--fetch", "--rid", RID, "--rid", OTHER_RID],
+            &[
+                "--announce",
+                "--timeout",
+                "1m",
+                "--wait-ack",
+                "--ack-timeout",
+                "5s",
+            ],
+            &[
+                "--replicas",
+                "5",
+                "--seed",
+                &addr,
+                "--fallback-seed",
+                OTHER_NID,
+            ],
+            &["--replicas", "max", "--no-timeout", "--shuffle-seed", "7"],
+            &[
+                "--no-announce",
+                "--timeout",
+                "1500ms",
+                "--max-bytes",
+                "1GiB",
+            ],
+            &["--inventory"],
+        ] {
+            let (mode, reparsed) = round_trip(args);
+            assert_eq!(mode, reparsed, "{args:?}");
+        }
+    }
+
+    #[test]
+    fn to_args_omits_defaults() {
+        assert!(SyncSettings::default().to_args().is_empty());
+        assert!(sync(&[]).mode().to_args().is_empty());
+        assert_eq!(SyncMode::Inventory.to_args(), ["--inventory"]);
+        assert_eq!(
+            sync(&["--replicas", "5", "--timeout", "30s"])
+                .mode()
+                .to_args(),
+            ["--replicas", "5", "--timeout", "30s"]
+        );
+    }
+
+    #[test]
+    fn to_args_sorts_seeds() {
+        let a = settings(&["--seed", NID, "--seed", OTHER_NID]);
+        let b = settings(&["--seed", OTHER_NID, "--seed", NID]);
+
+        assert_eq!(a.to_args(), ["--seed", OTHER_NID, "--seed", NID]);
+        assert_eq!(a.to_args(), b.to_args());
+
+        // The order is not kept, but the seeds are.
+        let args = a.to_args();
+        let reparsed = settings(&args.iter().map(String::as_str).collect::<Vec<_>>());
+        assert!(reparsed.same_seeds_as(&a));
+    }
+
+    #[test]
+    fn replicas_flag() {
+        assert_eq!(
+            settings(&["--replicas", "3"]).replicas,
+            ReplicaTarget::Exact(3)
+        );
+        assert_eq!(settings(&["-r", "5"]).replicas, ReplicaTarget::Exact(5));
+        assert_eq!(
+            settings(&["--replicas", "max"]).replicas,
+            ReplicaTarget::Max
+        );
+        assert_eq!(validate(&["--replicas", "max"]), Ok(()));
+        assert_eq!(
+            validate(&["--replicas", "0"]),
+            Err(OptionsError::Settings(SettingsError::ZeroReplicas))
+        );
+    }
+
+    #[test]
+    fn replica_target() {
+        assert_eq!(ReplicaTarget::default(), ReplicaTarget::Exact(3));
+        assert!(ReplicaTarget::Max.is_unbounded());
+        assert!(!ReplicaTarget::Exact(3).is_unbounded());
+        assert_eq!("max".parse(), Ok(ReplicaTarget::Max));
+        assert_eq!("3".parse(), Ok(ReplicaTarget::Exact(3)));
+    }
+
+    #[test]
+    fn timeout_bounds() {
+        let timeout = |t: &str| validate(&["--timeout", t]);
+
+        assert_eq!(
+            timeout("0"),
+            Err(OptionsError::Settings(SettingsError::ZeroTimeout))
+        );
+        assert_eq!(timeout("1h"), Ok(()));
+        assert_eq!(timeout("3600"), Ok(()));
+        assert_eq!(
+            timeout("3601"),
+            Err(OptionsError::Settings(SettingsError::TimeoutTooLong {
+                timeout: time::Duration::from_secs(3601)
+            }))
+        );
+        assert_eq!(
+            timeout("3601").unwrap_err().to_string(),
+            "timeout `1h1s` exceeds the maximum of `1h`, use `--no-timeout` to wait indefinitely"
+        );
+    }
+
+    #[test]
+    fn builder_timeout_bounds() {
+        let build = |t| SyncSettings::builder().timeout(t).build();
+
+        assert!(build(MAX_SYNC_TIMEOUT).is_ok());
+        assert_eq!(
+            build(MAX_SYNC_TIMEOUT + time::Duration::from_millis(1)),
+            Err(SettingsError::TimeoutTooLong {
+                timeout: MAX_SYNC_TIMEOUT + time::Duration::from_millis(1)
+            })
+        );
+    }
+
+    #[test]
+    fn invert_direction() {
+        assert_eq!(SyncDirection::Fetch.invert(), Some(SyncDirection::Announce));
+        assert_eq!(SyncDirection::Announce.invert(), Some(SyncDirection::Fetch));
+        assert_eq!(SyncDirection::Both.invert(), None);
+    }
+
+    #[test]
+    fn direction_flags() {
+        assert_eq!(direction(&[]), SyncDirection::Both);
+        assert_eq!(direction(&["--fetch"]), SyncDirection::Fetch);
+        assert_eq!(direction(&["--announce"]), SyncDirection::Announce);
+        assert_eq!(direction(&["--fetch", "--announce"]), SyncDirection::Both);
+        assert_eq!(direction(&["--no-announce"]), SyncDirection::Fetch);
+        assert_eq!(direction(&["--no-fetch"]), SyncDirection::Announce);
+        assert_eq!(
+            direction(&["--fetch", "--no-announce"]),
+            SyncDirection::Fetch
+        );
+        assert_eq!(
+            direction(&["--announce", "--no-fetch"]),
+            SyncDirection::Announce
+        );
+    }
+
+    #[test]
+    fn direction_flag_conflicts_with_negation() {
+        for args in [["--fetch", "--no-fetch"], ["--announce", "--no-announce"]] {
+            let err = try_parse(&args).unwrap_err();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+        }
+    }
+
+    #[test]
+    fn all_directions_negated() {
+        let err = validate(&["--no-fetch", "--no-announce"]).unwrap_err();
+
+        assert_eq!(err, OptionsError::NothingToSync);
+        assert!(err.to_string().contains("nothing to do"));
+    }
+
+    #[test]
+    fn single_direction_negated() {
+        assert_eq!(validate(&["--no-fetch"]), Ok(()));
+        assert_eq!(validate(&["--no-announce"]), Ok(()));
+    }
+
+    #[test]
+    fn display_direction() {
+        assert_eq!(SyncDirection::Fetch.to_string(), "fetch");
+        assert_eq!(SyncDirection::Announce.to_string(), "announce");
+        assert_eq!(SyncDirection::Both.to_string(), "fetch and announce");
+    }
+
+    #[test]
+    fn subcommand_name() {
+        assert_eq!(parse(&[]).subcommand_name(), "sync");
+        assert_eq!(parse(&["--fetch"]).subcommand_name(), "sync");
+        assert_eq!(parse(&["status"]).subcommand_name(), "status");
+        assert_eq!(parse(&["--inventory"]).subcommand_name(), "inventory");
+        assert_eq!(parse(&["inventory"]).subcommand_name(), "inventory");
+        assert_eq!(
+            parse(&["completions", "bash"]).subcommand_name(),
+            "completions"
+        );
+    }
+
+    #[test]
+    fn resolve_known_name() {
+        let names = BTreeMap::from([("heartwood".to_owned(), rid(RID))]);
+
+        assert_eq!(
+            sync(&["--name", "heartwood"]).resolve_name(&names),
+            Ok(vec![rid(RID)])
+        );
+    }
+
+    #[test]
+    fn resolve_unknown_name() {
+        let names = BTreeMap::from([
+            ("heartwood".to_owned(), rid(RID)),
+            ("radicle".to_owned(), rid(RID)),
+        ]);
+        let err = sync(&["--name", "heartwod"])
+            .resolve_name(&names)
+            .unwrap_err();
+
+        assert_eq!(err.available, ["heartwood", "radicle"]);
+        assert_eq!(
+            err.to_string(),
+            "unknown repository name `heartwod`, available names: heartwood, radicle"
+        );
+    }
+
+    #[test]
+    fn name_conflicts_with_rid() {
+        let err = try_parse(&["--rid", RID, "--name", "heartwood"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn sync_mode_tag() {
+        let repo = serde_json::to_value(sync(&["--fetch"]).mode()).unwrap();
+        assert_eq!(repo["mode"], "repo");
+        assert_eq!(repo["direction"], "fetch");
+
+        let inventory = serde_json::to_value(SyncMode::Inventory).unwrap();
+        assert_eq!(inventory, serde_json::json!({ "mode": "inventory" }));
+    }
+
+    #[test]
+    fn requires_rid() {
+        assert!(sync(&[]).mode().requires_rid());
+        assert!(!SyncMode::Inventory.requires_rid());
+    }
+
+    #[test]
+    fn debug_report() {
+        let opts = parse(&["--debug", "--announce", "--seed", NID]);
+        let report = opts.debug_report();
+
+        assert!(opts.debug());
+        let SyncMode::Repo {
+            settings,
+            direction,
+            ..
+        } = &report.mode
+        else {
+            panic!("expected a repository sync");
+        };
+        assert_eq!(*direction, SyncDirection::Announce);
+        assert_eq!(settings.seeds.len(), 1);
+        assert!(report.to_string().contains("direction: Announce"));
+
+        // The single seed cannot meet the default target of 3.
+        let warning = "replica target of 3 cannot be met, only 1 seed(s) are available";
+        assert_eq!(report.warnings, [warning]);
+        assert!(report
+            .to_string()
+            .ends_with(&format!("Warnings:\n{warning}")));
+
+        let report = parse(&[
+            "--replicas",
+            "2",
+            "--seed",
+            NID,
+            "--fallback-seed",
+            OTHER_NID,
+        ])
+        .debug_report();
+        assert!(report.warnings.is_empty());
+        assert!(!report.to_string().contains("Warnings:"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn debug_report_json() {
+        let report = parse(&["--debug", "--seed", NID, "--timeout", "30s"]).debug_report();
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["args"]["debug"], true);
+        assert_eq!(json["args"]["settings"]["seeds"], serde_json::json!([NID]));
+        assert_eq!(json["args"]["settings"]["timeout"], 30);
+        assert_eq!(json["mode"]["mode"], "repo");
+        assert_eq!(
+            json["warnings"],
+            serde_json::json!(["replica target of 3 cannot be met, only 1 seed(s) are available"])
+        );
+    }
+
+    #[test]
+    fn wait_ack() {
+        assert_eq!(settings(&[]).ack_timeout, None);
+        assert_eq!(
+            settings(&["--wait-ack"]).ack_timeout,
+            Some(DEFAULT_ACK_TIMEOUT)
+        );
+        assert_eq!(
+            settings(&["--announce", "--wait-ack", "--ack-timeout", "30s"]).ack_timeout,
+            Some(time::Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn wait_ack_only_after_announcing() {
+        let args = sync(&["--wait-ack"]).settings;
+        assert_eq!(args.resolve(&SyncDirection::Fetch).ack_timeout, None);
+
+        let err = try_parse(&["--ack-timeout", "30s"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+
+        assert_eq!(
+            validate(&["--fetch", "--wait-ack"]),
+            Err(OptionsError::WaitAckWithoutAnnounce)
+        );
+        assert_eq!(
+            validate(&["--no-announce", "--wait-ack"]),
+            Err(OptionsError::WaitAckWithoutAnnounce)
+        );
+        assert_eq!(validate(&["--fetch", "--announce", "--wait-ack"]), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn resolved_options_json() {
+        let json = serde_json::to_value(parse(&["--seed", NID]).resolved()).unwrap();
+
+        assert_eq!(json["mode"], "repo");
+        assert_eq!(json["direction"], "both");
+        assert_eq!(json["replicas"], 3);
+        assert_eq!(json["seeds"], serde_json::json!([NID]));
+        assert_eq!(json["timeout"], 9);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn resolved_options_json_sub_second_timeout() {
+        let json = serde_json::to_value(parse(&["--timeout", "1500ms"]).resolved()).unwrap();
+        assert_eq!(json["timeout"], 1.5);
+
+        let json = serde_json::to_value(parse(&["--no-timeout"]).resolved()).unwrap();
+        assert_eq!(json["timeout"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn resolve_repo_path() {
+        assert_eq!(
+            sync(&["--repo-path", "/src/heartwood"]).resolve_repo_path(&resolver()),
+            Ok(vec![rid(RID)])
+        );
+    }
+
+    #[test]
+    fn resolve_repo_path_not_a_repository() {
+        let err = sync(&["--repo-path", "/tmp"])
+            .resolve_repo_path(&resolver())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "`/tmp` is not a Radicle repository");
+    }
+
+    #[test]
+    fn repo_path_conflicts_with_rid() {
+        let err = try_parse(&["--rid", RID, "--repo-path", "/src/heartwood"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn resolve_rid_from_working_directory() {
+        let repo = TempDir::new("repo");
+        fs::create_dir_all(repo.0.join(".rad")).unwrap();
+        fs::write(repo.0.join(RID_FILE), format!("{RID}\n")).unwrap();
+        let subdir = repo.0.join("src");
+        fs::create_dir_all(&subdir).unwrap();
+
+        assert_eq!(resolve_rid(&[], &repo.0).unwrap(), [rid(RID)]);
+        assert_eq!(resolve_rid(&[], &subdir).unwrap(), [rid(RID)]);
+    }
+
+    #[test]
+    fn resolve_rid_outside_repository() {
+        let dir = TempDir::new("not-a-repo");
+        let err = resolve_rid(&[], &dir.0).unwrap_err();
+
+        assert!(matches!(err, RidError::NotFound { .. }));
+        assert!(err
+            .to_string()
+            .contains("is not inside a Radicle repository"));
+    }
+
+    #[test]
+    fn resolve_rid_prefers_given_repository() {
+        let dir = TempDir::new("given");
+
+        assert_eq!(
+            resolve_rid(&["--rid", OTHER_RID], &dir.0).unwrap(),
+            [rid(OTHER_RID)]
+        );
+        assert_eq!(
+            resolve_rid(&["--name", "heartwood"], &dir.0).unwrap(),
+            [rid(OTHER_RID)]
+        );
+        assert_eq!(
+            resolve_rid(&["--repo-path", "/src/heartwood"], &dir.0).unwrap(),
+            [rid(RID)]
+        );
+        assert!(matches!(
+            resolve_rid(&["--repo-path", "/tmp"], &dir.0),
+            Err(RidError::NotARepository(_))
+        ));
+        assert!(matches!(
+            resolve_rid(&["--name", "radicle"], &dir.0),
+            Err(RidError::UnknownName(_))
+        ));
+    }
+
+    #[test]
+    fn resolve_rid_ignored_for_inventory() {
+        let dir = TempDir::new("inventory");
+
+        assert!(resolve_rid(&["--inventory"], &dir.0).unwrap().is_empty());
+        assert!(resolve_rid(&["status", "--all"], &dir.0)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn display_sync_mode() {
+        assert_eq!(
+            sync(&[]).mode().to_string(),
+            "syncing repo (fetch and announce) with 3 replicas and 0 seeds"
+        );
+        assert_eq!(
+            sync(&["--fetch", "--replicas", "1", "--seed", NID])
+                .mode()
+                .to_string(),
+            "syncing repo (fetch) with 1 replica and 1 seed"
+        );
+        assert_eq!(
+            sync(&["--rid", RID, "--rid", OTHER_RID, "--replicas", "max"])
+                .mode()
+                .to_string(),
+            "syncing 2 repos (fetch and announce) with as many replicas as possible and 0 seeds"
+        );
+        assert_eq!(
+            sync(&["--announce", "--wait-ack", "--ack-timeout", "30s"])
+                .mode()
+                .to_string(),
+            "syncing repo (announce) with 3 replicas and 0 seeds, waiting up to 30s for \
+             acknowledgements"
+        );
+        assert_eq!(SyncMode::Inventory.to_string(), "announcing inventory");
+    }
+
+    #[test]
+    fn dry_run_flag() {
+        assert!(parse(&["--dry-run"]).dry_run());
+        assert!(parse(&["status", "--dry-run"]).dry_run());
+        assert!(!parse(&[]).dry_run());
+    }
+
+    #[test]
+    fn display_operation() {
+        assert_eq!(
+            sync(&["status"]).op.unwrap().to_string(),
+            "showing sync status of repo, sorted by sync status in ascending order"
+        );
+        assert_eq!(
+            sync(&["status", "--all", "--sort-by", "alias", "--order", "desc"])
+                .op
+                .unwrap()
+                .to_string(),
+            "showing sync status of all repos, sorted by alias in descending order"
+        );
+    }
+
+    #[test]
+    fn status_all() {
+        assert!(matches!(
+            sync(&["status", "--all"]).op,
+            Some(Operation::Status { all: true, .. })
+        ));
+        assert!(matches!(
+            sync(&["status"]).op,
+            Some(Operation::Status { all: false, .. })
+        ));
+    }
+
+    #[test]
+    fn status_all_conflicts_with_rid() {
+        let err = try_parse(&["status", "--all", "--rid", RID]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        // Given before the subcommand, `--rid` is only caught by validation.
+        assert_eq!(
+            validate(&["--rid", RID, "status", "--all"]),
+            Err(OptionsError::AllWithRepo)
+        );
+    }
+
+    #[test]
+    fn inventory_spellings() {
+        assert_eq!(sync(&["inventory"]).mode(), SyncMode::Inventory);
+        assert_eq!(sync(&["--inventory"]).mode(), SyncMode::Inventory);
+        assert_eq!(
+            sync(&["--inventory", "inventory"]).mode(),
+            SyncMode::Inventory
+        );
+        assert_eq!(validate(&["--inventory", "inventory"]), Ok(()));
+    }
+
+    #[test]
+    fn direction_with_inventory() {
+        let err = validate(&["--fetch", "--inventory"]).unwrap_err();
+
+        assert_eq!(
+            err,
+            OptionsError::DirectionWithInventory { flag: "--fetch" }
+        );
+        assert_eq!(
+            err.to_string(),
+            "`--fetch` cannot be used with `--inventory`: announcing the inventory does not \
+             fetch or sync a repository; drop either `--fetch` or `--inventory`"
+        );
+        assert_eq!(
+            validate(&["--no-announce", "inventory"]),
+            Err(OptionsError::DirectionWithInventory {
+                flag: "--no-announce"
+            })
+        );
+    }
+
+    #[test]
+    fn repo_with_inventory() {
+        let err = validate(&["--rid", RID, "--inventory"]).unwrap_err();
+
+        assert_eq!(err, OptionsError::RepoWithInventory { flag: "--rid" });
+        assert_eq!(
+            err.to_string(),
+            "`--rid` cannot be used with `--inventory`: announcing the inventory does not \
+             target a repository; drop either `--rid` or `--inventory`"
+        );
+        assert_eq!(
+            validate(&["--name", "heartwood", "inventory"]),
+            Err(OptionsError::RepoWithInventory { flag: "--name" })
+        );
+    }
+
+    #[test]
+    fn bash_completions() {
+        let mut script = Vec::new();
+        write_completions(Shell::Bash, &mut script);
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script.contains("--replicas"));
+        assert!(script.contains("status"));
+        assert!(script.contains("nid alias status"));
+    }
+
+    #[test]
+    fn completions_shell() {
+        assert_eq!(
+            parse(&["completions", "zsh"]).completions(),
+            Some(Shell::Zsh)
+        );
+        assert_eq!(parse(&[]).completions(), None);
+
+        let err = try_parse(&["completions", "tcsh"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn config_file() {
+        let config = load_config(
+            "config",
+            &format!("replicas = 2\ntimeout = \"30s\"\nseeds = [\"{NID}\"]\nsort_by = \"alias\"\n"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            SyncConfig {
+                replicas: Some(Replicas::Count(2)),
+                timeout: Some(time::Duration::from_secs(30)),
+                seeds: Some(vec![seed(NID)]),
+                sort_by: Some(SortBy::Alias),
+            }
+        );
+        assert_eq!(
+            settings_with_config(config, &[], &[]),
+            SyncSettings {
+                replicas: ReplicaTarget::Exact(2),
+                explicit_replicas: true,
+                seeds: vec![seed(NID)],
+                timeout: Some(time::Duration::from_secs(30)),
+                ..SyncSettings::default()
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn config_replicas() {
+        let replicas = |value| load_config("replicas", &format!("replicas = {value}"));
+
+        assert_eq!(replicas("\"auto\"").unwrap().replicas, Some(Replicas::Auto));
+        assert_eq!(replicas("\"max\"").unwrap().replicas, Some(Replicas::Max));
+        assert!(replicas("\"many\"").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn config_precedence() {
+        let config = SyncConfig {
+            replicas: Some(Replicas::Count(2)),
+            timeout: Some(time::Duration::from_secs(30)),
+            ..SyncConfig::default()
+        };
+
+        let settings = settings_with_config(config.clone(), &[(ENV_SYNC_TIMEOUT, "1m")], &[]);
+        assert_eq!(settings.replicas, ReplicaTarget::Exact(2));
+        assert_eq!(settings.timeout, Some(time::Duration::from_secs(60)));
+
+        let settings =
+            settings_with_config(config, &[(ENV_SYNC_REPLICAS, "4")], &["--timeout", "5s"]);
+        assert_eq!(settings.replicas, ReplicaTarget::Exact(4));
+        assert_eq!(settings.timeout, Some(time::Duration::from_secs(5)));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn config_unknown_key() {
+        let err = load_config("unknown", "replica = 2").unwrap_err();
+
+        assert!(matches!(err, ConfigError::Parse { .. }));
+        assert!(err.to_string().contains("unknown field `replica`"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn config_mistyped_key() {
+        assert!(matches!(
+            load_config("mistyped", "timeout = \"soon\""),
+            Err(ConfigError::Parse { .. })
+        ));
+        assert!(matches!(
+            load_config("mistyped-seeds", "seeds = \"z6Mk\""),
+            Err(ConfigError::Parse { .. })
+        ));
+        assert!(matches!(
+            load_config("malformed", "replicas ="),
+            Err(ConfigError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(not(feature = "serde"))]
+    fn config_unsupported() {
+        assert!(matches!(
+            SyncConfig::load(Path::new(CONFIG_FILE)),
+            Err(ConfigError::Unsupported)
+        ));
+    }
+
+    #[test]
+    fn repo_request() {
+        let mode = sync(&["--fetch", "--seed", NID, "--timeout", "30s"]).mode();
+
+        assert_eq!(
+            mode.to_request(Some(&rid(RID))),
+            SyncRequest {
+                mode: SyncRequestKind::Repo,
+                rid: Some(rid(RID)),
+                direction: Some(SyncDirection::Fetch),
+                replicas: Some(ReplicaTarget::Exact(3)),
+                seeds: vec![seed(NID)],
+                timeout: Some(time::Duration::from_secs(30)),
+            }
+        );
+    }
+
+    #[test]
+    fn inventory_request() {
+        assert_eq!(
+            SyncMode::Inventory.to_request(Some(&rid(RID))),
+            SyncRequest {
+                mode: SyncRequestKind::Inventory,
+                rid: None,
+                direction: None,
+                replicas: None,
+                seeds: Vec::new(),
+                timeout: None,
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serialize_request() {
+        let mode = sync(&["--timeout", "500ms", "--replicas", "max"]).mode();
+
+        assert_eq!(
+            serde_json::to_value(mode.to_request(Some(&rid(RID)))).unwrap(),
+            serde_json::json!({
+                "mode": "repo",
+                "rid": RID,
+                "direction": "both",
+                "replicas": "max",
+                "seeds": [],
+                "timeout_ms": 500,
+            })
+        );
+    }
+
+    #[test]
+    fn verbosity() {
+        assert_eq!(parse(&[]).verbosity(), Verbosity::Normal);
+        assert_eq!(parse(&["-q"]).verbosity(), Verbosity::Quiet);
+        assert_eq!(parse(&["--quiet"]).verbosity(), Verbosity::Quiet);
+        assert_eq!(parse(&["-v"]).verbosity(), Verbosity::Verbose);
+        assert!(parse(&["-v"]).verbose());
+        assert!(!parse(&["-q"]).verbose());
+    }
+
+    #[test]
+    fn quiet_conflicts_with_verbose() {
+        let err = try_parse(&["-q", "-v"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn fingerprint_ignores_output_flags() {
+        let base = fingerprint(&["--seed", NID]);
+
+        assert_eq!(base.len(), 16);
+        assert_eq!(fingerprint(&["--seed", NID, "-v"]), base);
+        assert_eq!(fingerprint(&["--seed", NID, "-q"]), base);
+        assert_eq!(fingerprint(&["--seed", NID, "--debug", "--json"]), base);
+        assert_eq!(fingerprint(&["--seed", NID, "--dry-run"]), base);
+
+        let status = fingerprint(&["--seed", NID, "status"]);
+        assert_eq!(fingerprint(&["--seed", NID, "status", "--compact"]), status);
+        assert_eq!(
+            fingerprint(&[
+                "--seed",
+                NID,
+                "status",
+                "--order",
+                "desc",
+                "--count-only",
+                "--template",
+                "{nid}",
+                "--max-col-width",
+                "8",
+                "--fields",
+                "nid"
+            ]),
+            status
+        );
+    }
+
+    #[test]
+    fn fingerprint_changes_with_settings() {
+        let base = fingerprint(&["--seed", NID]);
+
+        assert_ne!(fingerprint(&["--seed", OTHER_NID]), base);
+        assert_ne!(fingerprint(&["--seed", NID, "--rid", RID]), base);
+        assert_ne!(fingerprint(&["--seed", NID, "status"]), base);
+        assert_ne!(
+            fingerprint(&["--seed", NID, "status", "--status-timeout", "1s"]),
+            fingerprint(&["--seed", NID, "status"])
+        );
+        assert_ne!(fingerprint(&["--seed", NID, "--include-known"]), base);
+        assert_ne!(
+            fingerprint(&["--seed", NID, "--include-known", "--known-filter", "alice"]),
+            fingerprint(&["--seed", NID, "--include-known", "--known-filter", "bob"])
+        );
+    }
+
+    #[test]
+    fn repeated_rids() {
+        let SyncMode::Repo { rids, .. } = sync(&["--rid", RID, "--rid", OTHER_RID]).mode() else {
+            panic!("expected a repository sync");
+        };
+        assert_eq!(rids, [rid(RID), rid(OTHER_RID)]);
+
+        let SyncMode::Repo { rids, .. } =
+            sync(&["--rid", RID, "--rid", OTHER_RID, "--rid", RID]).mode()
+        else {
+            panic!("expected a repository sync");
+        };
+        assert_eq!(rids, [rid(RID), rid(OTHER_RID)]);
+    }
+
+    #[test]
+    fn rids_not_part_of_inventory() {
+        assert_eq!(
+            sync(&["--rid", RID, "--rid", OTHER_RID, "--inventory"]).mode(),
+            SyncMode::Inventory
+        );
+    }
+
+    #[test]
+    fn compact_glyphs() {
+        assert_eq!(SyncState::Synced.glyph(), '✓');
+        assert_eq!(SyncState::OutOfSync.glyph(), '✗');
+        assert_eq!(SyncState::Unknown.glyph(), '~');
+    }
+
+    #[test]
+    fn compact_flag() {
+        assert!(matches!(
+            sync(&["status", "--compact"]).op,
+            Some(Operation::Status { compact: true, .. })
+        ));
+        assert!(matches!(
+            sync(&["status"]).op,
+            Some(Operation::Status { compact: false, .. })
+        ));
+    }
+
+    #[test]
+    fn sort_is_stable() {
+        let mut rows = vec![
+            row(NID, "bob", SyncState::Synced),
+            row(OTHER_NID, "alice", SyncState::OutOfSync),
+            row(OTHER_NID, "carol", SyncState::Synced),
+        ];
+        let aliases = |rows: &[Row]| rows.iter().map(|r| r.alias.unwrap()).collect::<Vec<_>>();
+
+        SortBy::Status.sort_rows(&mut rows);
+        assert_eq!(aliases(&rows), ["bob", "carol", "alice"]);
+
+        SortBy::Status.sort_rows_in(SortOrder::Desc, &mut rows);
+        assert_eq!(aliases(&rows), ["alice", "bob", "carol"]);
+    }
+
+    #[test]
+    fn count_only_exit_code() {
+        let summary = [
+            SyncState::Synced,
+            SyncState::OutOfSync,
+            SyncState::Unknown,
+            SyncState::OutOfSync,
+        ]
+        .into_iter()
+        .collect::<StatusSummary>();
+
+        assert_eq!(summary.out_of_sync, 2);
+        assert_eq!(summary.exit_code(), 2);
+        assert_eq!(StatusSummary::default().exit_code(), 0);
+    }
+
+    #[test]
+    fn count_only_exit_code_is_capped() {
+        let summary = StatusSummary {
+            out_of_sync: 300,
+            ..StatusSummary::default()
+        };
+        assert_eq!(summary.exit_code(), 255);
+    }
+
+    #[test]
+    fn count_only_flag() {
+        assert!(matches!(
+            sync(&["status", "--count-only"]).op,
+            Some(Operation::Status {
+                count_only: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn status_timeout() {
+        let op = sync(&["status", "--status-timeout", "500ms"]).op.unwrap();
+        assert_eq!(op.status_timeout(), time::Duration::from_millis(500));
+    }
+
+    #[test]
+    fn status_timeout_is_independent_of_timeout() {
+        let sync = sync(&["--timeout", "1m", "status"]);
+
+        assert_eq!(sync.op.unwrap().status_timeout(), DEFAULT_STATUS_TIMEOUT);
+        assert_eq!(sync.settings.timeout, Some(time::Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn render_template() {
+        let template = "{alias} is {status} ({nid}, {last_synced})"
+            .parse::<StatusTemplate>()
+            .unwrap();
+        let rows = [
+            row(NID, "alice", SyncState::Synced),
+            Row {
+                nid: nid(OTHER_NID),
+                alias: None,
+                state: SyncState::OutOfSync,
+            },
+        ];
+
+        assert_eq!(
+            rows.iter().map(|r| template.render(r)).collect::<Vec<_>>(),
+            [
+                format!("alice is synced ({NID}, -)"),
+                format!("- is out-of-sync ({OTHER_NID}, -)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn template_unknown_placeholder() {
+        assert_eq!(
+            "{nid} {age}".parse::<StatusTemplate>(),
+            Err(TemplateError::UnknownPlaceholder("age".to_owned()))
+        );
+        assert_eq!(
+            "{nid".parse::<StatusTemplate>(),
+            Err(TemplateError::Unclosed)
+        );
+
+        let err = try_parse(&["status", "--template", "{age}"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn truncate_cells() {
+        assert_eq!(truncate_cell("alice", 8), "alice");
+        assert_eq!(truncate_cell("alice", 5), "alice");
+        assert_eq!(truncate_cell("seed.radicle.xyz", 8), "seed.ra…");
+        assert_eq!(truncate_cell("ålice", 3), "ål…");
+        assert_eq!(truncate_cell("alice", 0), "");
+    }
+
+    #[test]
+    fn max_col_width_flag() {
+        assert!(matches!(
+            sync(&["status", "--max-col-width", "12"]).op,
+            Some(Operation::Status {
+                max_col_width: Some(12),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn status_help_examples() {
+        let help = try_parse(&["status", "--help"]).unwrap_err();
+        assert_eq!(help.kind(), clap::error::ErrorKind::DisplayHelp);
+
+        assert!(help
+            .to_string()
+            .contains("rad sync status --sort-by status --order desc"));
+    }
+
+    #[test]
+    fn sync_state_display() {
+        assert_eq!(SyncState::Synced.to_string(), "synced");
+        assert_eq!(SyncState::OutOfSync.to_string(), "out-of-sync");
+        assert_eq!(SyncState::Unknown.to_string(), "unknown");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn sync_state_serialize() {
+        for state in [SyncState::Synced, SyncState::OutOfSync, SyncState::Unknown] {
+            assert_eq!(
+                serde_json::to_value(state).unwrap(),
+                serde_json::Value::from(state.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn display_sort_by() {
+        assert_eq!(SortBy::Nid.to_string(), "node id");
+        assert_eq!(SortBy::Alias.to_string(), "alias");
+        assert_eq!(SortBy::Status.to_string(), "sync status");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn project_fields() {
+        let row = row(NID, "alice", SyncState::Synced);
+        let fields = match sync(&["status", "--fields", "nid,status"]).op {
+            Some(Operation::Status { fields, .. }) => fields,
+            _ => panic!("expected the status operation"),
+        };
+
+        assert_eq!(
+            serde_json::Value::Object(project_row(&row, &fields)),
+            serde_json::json!({ "nid": NID, "status": "synced" })
+        );
+        assert_eq!(
+            project_row(&row, &[]).keys().collect::<Vec<_>>(),
+            ["alias", "last_synced", "nid", "status"]
+        );
+    }
+
+    #[test]
+    fn project_unknown_field() {
+        let err = try_parse(&["status", "--fields", "nid,age"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn sort_order() {
+        let sort = |args: &[&str]| sync(args).op.unwrap().sort();
+
+        assert_eq!(sort(&["status"]), (SortBy::Status, SortOrder::Asc));
+        assert_eq!(
+            sort(&["status", "--order", "asc"]),
+            (SortBy::Status, SortOrder::Asc)
+        );
+        assert_eq!(
+            sort(&["status", "--sort-by", "status", "--order", "desc"]),
+            (SortBy::Status, SortOrder::Desc)
+        );
+    }
+
+    #[test]
+    fn sort_order_default_in_help() {
+        let help = try_parse(&["status", "--help"]).unwrap_err();
+        assert_eq!(help.kind(), clap::error::ErrorKind::DisplayHelp);
+
+        assert!(help.to_string().contains("[default: asc]"));
+    }
+
+    #[test]
+    fn parse_sort_by() {
+        for value in SortBy::value_variants() {
+            let name = value.to_possible_value().unwrap();
+            assert_eq!(name.get_name().parse::<SortBy>(), Ok(*value));
+        }
+    }
+
+    #[test]
+    fn parse_sort_by_error() {
+        let err = "size".parse::<SortBy>().unwrap_err();
+
+        assert_eq!(err.input, "size");
+        assert_eq!(
+            err.to_string(),
+            "invalid --sort-by field: `size`, expected one of nid, alias, status"
+        );
+    }
+
+    #[test]
+    fn sort_nodes() {
+        let mut peers = vec![
+            peer(NID, Some("bob")),
+            peer(THIRD_NID, Some("Alice")),
+            peer(OTHER_NID, Some("alice")),
+            peer(OTHER_NID, None),
+        ];
+        let order = |peers: &[KnownPeer]| {
+            peers
+                .iter()
+                .map(|p| (p.nid.to_string(), p.alias.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        SortBy::Alias.sort_nodes(&mut peers);
+        assert_eq!(
+            order(&peers),
+            order(&[
+                peer(OTHER_NID, None),
+                peer(THIRD_NID, Some("Alice")),
+                peer(OTHER_NID, Some("alice")),
+                peer(NID, Some("bob")),
+            ])
+        );
+
+        SortBy::Nid.sort_nodes(&mut peers);
+        assert_eq!(
+            order(&peers),
+            order(&[
+                peer(THIRD_NID, Some("Alice")),
+                peer(OTHER_NID, None),
+                peer(OTHER_NID, Some("alice")),
+                peer(NID, Some("bob")),
+            ])
+        );
+    }
+
+    #[test]
+    fn state_from_comparison() {
+        assert_eq!(
+            SyncState::from_comparison("a1b2", Some("a1b2")),
+            SyncState::Synced
+        );
+        assert_eq!(
+            SyncState::from_comparison("a1b2", Some("c3d4")),
+            SyncState::OutOfSync
+        );
+        assert_eq!(SyncState::from_comparison("a1b2", None), SyncState::Unknown);
+    }
+}
@@ -1,7 +1,87 @@
-use clap::Parser;
-use rad::Options;
+use clap::{error::ErrorKind, CommandFactory};
+use rad::{
+    format_duration, write_completions, Operation, Options, ResolvedOptions, SyncMode, Verbosity,
+};
 
 fn main() {
-    let opts = Options::parse();
-    println!("{opts:#?}");
+    let opts = Options::parse_with_config();
+    if let Some(shell) = opts.completions() {
+        write_completions(shell, &mut std::io::stdout());
+        return;
+    }
+    if let Err(err) = opts.validate() {
+        Options::command()
+            .error(ErrorKind::ValueValidation, err)
+            .exit();
+    }
+
+    if opts.json() {
+        if opts.debug() {
+            print_json(&opts.debug_report());
+        } else {
+            print_json(&opts.resolved());
+        }
+        return;
+    }
+    if opts.verbosity() == Verbosity::Quiet {
+        return;
+    }
+    if opts.dry_run() {
+        print_plan(&opts);
+        return;
+    }
+
+    if opts.debug() {
+        eprintln!("{}", opts.debug_report());
+    }
+}
+
+fn print_plan(opts: &Options) {
+    let ResolvedOptions { mode, op } = opts.resolved();
+
+    // Showing the status does not sync, so the operation is the plan.
+    let status = op.filter(|op| matches!(op, Operation::Status { .. }));
+    match &status {
+        Some(op) => println!("{op}"),
+        None => println!("{mode}"),
+    }
+    if let (SyncMode::Repo { rids, settings, .. }, true) = (&mode, opts.verbose()) {
+        for rid in rids {
+            println!("rid: {rid}");
+        }
+        if let Some(op) = status {
+            println!("status timeout: {}", format_duration(op.status_timeout()));
+            return;
+        }
+        match settings.timeout {
+            Some(timeout) => println!("timeout: {}", format_duration(timeout)),
+            None => println!("timeout: none"),
+        }
+        for seed in &settings.seeds {
+            println!("seed: {seed}");
+        }
+        for seed in &settings.fallback_seeds {
+            println!("fallback seed: {seed}");
+        }
+        if let Some(warning) = settings.replica_warning() {
+            eprintln!("warning: {warning}");
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn print_json(value: &impl serde::Serialize) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{json}"),
+        Err(err) => {
+            eprintln!("error: failed to serialize options: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json<T>(_value: &T) {
+    eprintln!("error: `--json` requires the `serde` feature");
+    std::process::exit(1);
 }